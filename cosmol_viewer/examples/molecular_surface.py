@@ -0,0 +1,15 @@
+from cosmol_viewer import Molecule, Scene, Surface, Viewer
+
+ligand_data = open("./examples/6fi1_ligand.sdf", "r", encoding="utf-8").read()
+ligand = Molecule.from_sdf(ligand_data)
+surface = Surface.from_molecule(ligand).opacity(0.5)
+
+scene = Scene()
+scene.add_shape_with_id("ligand", ligand)
+scene.add_shape_with_id("surface", surface)
+scene.recenter(ligand.get_center())
+
+viewer = Viewer.render(scene, width=800, height=500)
+
+print("Press Any Key to exit...", end="", flush=True)
+_ = input()
@@ -0,0 +1,196 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use eframe::egui::{self, Align2, Color32, FontId, Pos2, Rect, Vec2};
+use glam::{Mat4, Vec3, Vec4};
+
+use crate::Shape;
+use crate::scene::Scene;
+use crate::shapes::{LabelSize, SphereInstance};
+
+/// Side length in points of the screen cells used to look up occluding spheres.
+const CELL: f32 = 32.0;
+
+struct LabelItem {
+    text: String,
+    /// Anchor in scene units (already multiplied by `Scene::scale`).
+    position: Vec3,
+    size: LabelSize,
+    offset: Vec2,
+    occlusion: bool,
+    color: Color32,
+}
+
+/// Text labels of a scene, painted with egui on top of the 3D view.
+///
+/// egui draws the glyphs from its font atlas as screen-aligned quads, so the
+/// labels always face the camera. Occlusion is tested on the CPU against the
+/// sphere instances of the scene; sticks, cones and meshes never hide a label.
+pub(crate) struct LabelLayer {
+    items: Vec<LabelItem>,
+    /// Atom spheres (center, radius) in scene units, used for occlusion.
+    occluders: Vec<(Vec3, f32)>,
+    model: Mat4,
+    scale: f32,
+}
+
+impl LabelLayer {
+    /// `spheres` are the instances already uploaded for `scene`, in scene units.
+    ///
+    /// Returns `None` when the scene has no labels.
+    pub fn from_scene(scene: &Scene, spheres: &[SphereInstance]) -> Option<Self> {
+        // 默认文字颜色随背景深浅取黑或白
+        let [r, g, b] = scene.background_color;
+        let default_color = if 0.299 * r + 0.587 * g + 0.114 * b > 0.5 {
+            Vec3::ZERO
+        } else {
+            Vec3::ONE
+        };
+
+        let mut items = Vec::new();
+        let mut occlusion = false;
+        for shape in scene.resolved_shapes() {
+            let labels = match shape.as_ref() {
+                Shape::Labels(labels) => Cow::Borrowed(labels),
+                Shape::Measurement(m) => Cow::Owned(m.to_labels()),
+                _ => continue,
+            };
+            if !labels.style.visible {
+                continue;
+            }
+            occlusion |= labels.occlusion;
+            let c = labels.style.color.unwrap_or(default_color);
+            let color = Color32::from_rgba_unmultiplied(
+                (c.x.clamp(0.0, 1.0) * 255.0) as u8,
+                (c.y.clamp(0.0, 1.0) * 255.0) as u8,
+                (c.z.clamp(0.0, 1.0) * 255.0) as u8,
+                (labels.style.opacity.clamp(0.0, 1.0) * 255.0) as u8,
+            );
+            for (text, position) in labels.texts.iter().zip(&labels.positions) {
+                items.push(LabelItem {
+                    text: text.clone(),
+                    position: *position * scene.scale,
+                    size: labels.size,
+                    offset: Vec2::from(labels.offset),
+                    occlusion: labels.occlusion,
+                    color,
+                });
+            }
+        }
+        if items.is_empty() {
+            return None;
+        }
+
+        let occluders = if occlusion {
+            spheres
+                .iter()
+                .map(|s| (Vec3::from(s.position), s.radius))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Some(Self {
+            items,
+            occluders,
+            model: scene.model_matrix(),
+            scale: scene.scale,
+        })
+    }
+
+    pub fn paint(&self, painter: &egui::Painter, view: Mat4, projection: Mat4, rect: Rect) {
+        let mvp = projection * view * self.model;
+        let to_screen = |clip: Vec4| {
+            let ndc = clip.truncate() / clip.w;
+            Pos2::new(
+                rect.left() + (ndc.x + 1.0) * 0.5 * rect.width(),
+                rect.top() + (1.0 - ndc.y) * 0.5 * rect.height(),
+            )
+        };
+        // 视空间距离 w 处 1 Å 对应的屏幕高度为 focal / w
+        let focal = projection.y_axis.y * 0.5 * rect.height();
+
+        // 把遮挡球按屏幕格子分桶，每个标签只检查自己所在格子里的球
+        let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (i, &(center, radius)) in self.occluders.iter().enumerate() {
+            let clip = mvp * center.extend(1.0);
+            if clip.w <= 0.1 {
+                continue;
+            }
+            let pos = to_screen(clip);
+            let r = radius * focal / clip.w;
+            let lo = ((pos.x - r) / CELL).floor() as i32..=((pos.x + r) / CELL).floor() as i32;
+            for cx in lo {
+                for cy in ((pos.y - r) / CELL).floor() as i32..=((pos.y + r) / CELL).floor() as i32
+                {
+                    cells.entry((cx, cy)).or_default().push(i);
+                }
+            }
+        }
+
+        let eye = (view * self.model).inverse().transform_point3(Vec3::ZERO);
+        for item in &self.items {
+            let clip = mvp * item.position.extend(1.0);
+            if clip.w <= 0.1 {
+                continue;
+            }
+            let anchor = to_screen(clip);
+            if !rect.contains(anchor) {
+                continue;
+            }
+            if item.occlusion {
+                let key = (
+                    (anchor.x / CELL).floor() as i32,
+                    (anchor.y / CELL).floor() as i32,
+                );
+                let hidden = cells.get(&key).is_some_and(|ids| {
+                    ids.iter()
+                        .any(|&i| occludes(eye, item.position, self.occluders[i]))
+                });
+                if hidden {
+                    continue;
+                }
+            }
+
+            let size = match item.size {
+                LabelSize::Screen(size) => size,
+                LabelSize::World(size) => size * self.scale * focal / clip.w,
+            };
+            // 太小的字看不清，直接跳过
+            if size < 4.0 {
+                continue;
+            }
+            painter.text(
+                anchor + item.offset,
+                Align2::CENTER_CENTER,
+                &item.text,
+                FontId::proportional(size),
+                item.color,
+            );
+        }
+    }
+}
+
+/// Whether `sphere` blocks the line of sight from `eye` to `target`.
+///
+/// Spheres containing the target are ignored, so an atom does not hide its own label.
+fn occludes(eye: Vec3, target: Vec3, (center, radius): (Vec3, f32)) -> bool {
+    if center.distance_squared(target) <= radius * radius {
+        return false;
+    }
+    let to_target = target - eye;
+    let length = to_target.length();
+    let dir = to_target / length;
+    let oc = center - eye;
+    let along = oc.dot(dir);
+    if along <= 0.0 {
+        return false;
+    }
+    let d2 = oc.length_squared() - along * along;
+    let r2 = radius * radius;
+    if d2 > r2 {
+        return false;
+    }
+    // 射线首次进入球面的位置在目标之前才算遮挡
+    along - (r2 - d2).sqrt() < length
+}
@@ -1,3 +1,5 @@
+mod label_layer;
+mod ramachandran;
 mod shader;
 use crate::egui::IconData;
 use std::sync::{Arc, Mutex};
@@ -18,6 +20,7 @@ pub mod shapes;
 use crate::scene::Scene;
 
 pub mod scene;
+pub mod selection;
 use image::{ImageBuffer, Rgba};
 
 pub struct AppWrapper<L: Logger>(pub Arc<Mutex<Option<App<L>>>>);
@@ -147,6 +150,8 @@ impl<L: Logger> eframe::App for App<L> {
                     ));
                 }
             });
+
+        self.canvas.show_panels(ctx);
     }
 }
 
@@ -0,0 +1,222 @@
+// CCP4 / MRC density maps
+//
+// https://www.ccpem.ac.uk/mrc_format/mrc2014.php
+// https://www.ccp4.ac.uk/html/maplib.html
+
+use crate::shapes::VolumeGrid;
+use glam::{Mat3, Vec3};
+use std::io;
+use std::io::ErrorKind;
+
+const HEADER_LEN: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub struct Ccp4Map {
+    /// Map values, with `axes[0]` along the file's fastest (column) axis.
+    pub grid: VolumeGrid,
+    /// Unit cell a, b, c (Å) and alpha, beta, gamma (degrees).
+    pub cell: [f32; 6],
+    /// Grid samples along the whole unit cell (NX, NY, NZ).
+    pub sampling: [usize; 3],
+    /// Crystal axis (0 = X, 1 = Y, 2 = Z) of columns, rows and sections.
+    pub axis_order: [usize; 3],
+    /// Grid start of columns, rows and sections, in `sampling` units.
+    pub start: [i32; 3],
+    pub space_group: u32,
+    /// Mean and RMS deviation of the map values.
+    pub mean: f32,
+    pub rms: f32,
+    pub labels: Vec<String>,
+}
+
+impl Ccp4Map {
+    pub fn new(bytes: &[u8]) -> io::Result<Self> {
+        if bytes.len() < HEADER_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "File too short for a CCP4/MRC header",
+            ));
+        }
+
+        // MACHST: 0x44 0x41 为小端，0x11 0x11 为大端；旧文件可能没有，按 MODE 猜测
+        let little_endian = match &bytes[212..214] {
+            [0x44, 0x41] | [0x44, 0x44] => true,
+            [0x11, 0x11] => false,
+            _ => u32::from_le_bytes(bytes[12..16].try_into().unwrap()) < 16,
+        };
+        let word = |i: usize| -> [u8; 4] { bytes[i * 4..i * 4 + 4].try_into().unwrap() };
+        let int = |i: usize| {
+            if little_endian {
+                i32::from_le_bytes(word(i))
+            } else {
+                i32::from_be_bytes(word(i))
+            }
+        };
+        let float = |i: usize| {
+            if little_endian {
+                f32::from_le_bytes(word(i))
+            } else {
+                f32::from_be_bytes(word(i))
+            }
+        };
+
+        let dims = [int(0), int(1), int(2)];
+        if dims.iter().any(|&d| d <= 0) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid map dimensions {:?}", dims),
+            ));
+        }
+        let dims = dims.map(|d| d as usize);
+        let mode = int(3);
+        let start = [int(4), int(5), int(6)];
+        let mut sampling = [int(7), int(8), int(9)].map(|n| n.max(0) as usize);
+        let cell = [
+            float(10),
+            float(11),
+            float(12),
+            float(13),
+            float(14),
+            float(15),
+        ];
+        let axis_order = [int(16), int(17), int(18)].map(|a| (a - 1) as usize);
+        let mut sorted = axis_order;
+        sorted.sort();
+        if sorted != [0, 1, 2] {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid axis order {:?}", axis_order.map(|a| a + 1)),
+            ));
+        }
+        let space_group = int(22).max(0) as u32;
+        let ext_header = int(23).max(0) as usize;
+        // MRC2014 的原点（Å），CCP4 文件一般为 0
+        let origin = Vec3::new(float(49), float(50), float(51));
+        let n_labels = (int(55).clamp(0, 10)) as usize;
+        let labels = (0..n_labels)
+            .map(|i| {
+                let off = 224 + i * 80;
+                String::from_utf8_lossy(&bytes[off..off + 80])
+                    .trim_end_matches(['\0', ' '])
+                    .to_string()
+            })
+            .collect();
+
+        let n = dims[0] * dims[1] * dims[2];
+        let data = &bytes[HEADER_LEN + ext_header.min(bytes.len() - HEADER_LEN)..];
+        let values: Vec<f32> = match mode {
+            0 => data.iter().take(n).map(|&b| b as i8 as f32).collect(),
+            1 => data
+                .chunks_exact(2)
+                .take(n)
+                .map(|c| {
+                    let b = [c[0], c[1]];
+                    if little_endian {
+                        i16::from_le_bytes(b) as f32
+                    } else {
+                        i16::from_be_bytes(b) as f32
+                    }
+                })
+                .collect(),
+            2 => data
+                .chunks_exact(4)
+                .take(n)
+                .map(|c| {
+                    let b = [c[0], c[1], c[2], c[3]];
+                    if little_endian {
+                        f32::from_le_bytes(b)
+                    } else {
+                        f32::from_be_bytes(b)
+                    }
+                })
+                .collect(),
+            6 => data
+                .chunks_exact(2)
+                .take(n)
+                .map(|c| {
+                    let b = [c[0], c[1]];
+                    if little_endian {
+                        u16::from_le_bytes(b) as f32
+                    } else {
+                        u16::from_be_bytes(b) as f32
+                    }
+                })
+                .collect(),
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    format!("Unsupported map mode {}", mode),
+                ));
+            }
+        };
+        if values.len() < n {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("Expected {} map values, found {}", n, values.len()),
+            ));
+        }
+
+        // 有的 EM 文件 NX/NY/NZ 为 0，此时把文件中的网格当作整个晶胞
+        for (c, &axis) in axis_order.iter().enumerate() {
+            if sampling[axis] == 0 {
+                sampling[axis] = dims[c];
+            }
+        }
+
+        // 每个晶轴方向上一个格点的步长
+        let cell_axes = cell_matrix(cell);
+        let steps: [Vec3; 3] =
+            std::array::from_fn(|axis| cell_axes.col(axis) / sampling[axis] as f32);
+        let axes = axis_order.map(|axis| steps[axis]);
+        // 与 ChimeraX 一致：ORIGIN 非零时优先使用它，否则用 N*START
+        let grid_origin = if origin != Vec3::ZERO {
+            origin
+        } else {
+            axes[0] * start[0] as f32 + axes[1] * start[1] as f32 + axes[2] * start[2] as f32
+        };
+
+        let grid = VolumeGrid {
+            dims,
+            origin: grid_origin,
+            axes,
+            values,
+        };
+
+        let (mean, sigma) = grid.mean_and_sigma();
+        Ok(Self {
+            grid,
+            cell,
+            sampling,
+            axis_order,
+            start,
+            space_group,
+            mean,
+            rms: sigma,
+            labels,
+        })
+    }
+
+    /// Whether the map covers the whole unit cell, so it can be repeated periodically.
+    pub fn is_periodic(&self) -> bool {
+        (0..3).all(|c| self.grid.dims[c] >= self.sampling[self.axis_order[c]])
+    }
+
+    /// Absolute map value `mean + sigma * rms`.
+    pub fn sigma_level(&self, sigma: f32) -> f32 {
+        self.mean + sigma * self.rms
+    }
+}
+
+/// Columns are the unit cell vectors a, b, c in Cartesian space (a along X, b in the XY plane).
+pub fn cell_matrix(cell: [f32; 6]) -> Mat3 {
+    let [a, b, c, alpha, beta, gamma] = cell;
+    let (alpha, beta, gamma) = (alpha.to_radians(), beta.to_radians(), gamma.to_radians());
+    let cx = beta.cos();
+    let cy = (alpha.cos() - beta.cos() * gamma.cos()) / gamma.sin();
+    let cz = (1.0 - cx * cx - cy * cy).max(0.0).sqrt();
+    Mat3::from_cols(
+        Vec3::new(a, 0.0, 0.0),
+        Vec3::new(b * gamma.cos(), b * gamma.sin(), 0.0),
+        Vec3::new(c * cx, c * cy, c * cz),
+    )
+}
@@ -0,0 +1,313 @@
+// Small-molecule CIF files (core dictionary: _cell_*, _atom_site_*, _atom_site_aniso_*)
+//
+// https://www.iucr.org/resources/cif/dictionaries/cif_core
+// https://www.iucr.org/resources/cif/spec/version1.1/cifsyntax
+//
+// 只读第一个数据块的不对称单元，不做对称操作展开。
+
+use crate::parser::ccp4::cell_matrix;
+use crate::parser::utils::AtomGeneric;
+use glam::{Mat3, Vec3};
+use na_seq::Element;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::io;
+use std::io::ErrorKind;
+
+#[derive(Clone, Debug)]
+pub struct Cif {
+    pub ident: String,
+    /// Unit cell a, b, c (Å) and alpha, beta, gamma (degrees).
+    pub cell: [f32; 6],
+    pub space_group: Option<String>,
+    /// Atoms with Cartesian positions (Å) and displacement tensors (Å²).
+    pub atoms: Vec<AtomGeneric>,
+}
+
+/// A loop: column tags and the values of each row.
+struct Table {
+    tags: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn column(&self, tag: &str) -> Option<usize> {
+        self.tags.iter().position(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Splits a line into values, keeping quoted strings together.
+fn tokenize(line: &str, out: &mut Vec<String>) {
+    let mut chars = line.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '#' {
+            break;
+        }
+        if c == '\'' || c == '"' {
+            // 引号只有后面跟空白或行尾时才算结束
+            chars.next();
+            let mut end = line.len();
+            while let Some((i, d)) = chars.next() {
+                if d == c && chars.peek().is_none_or(|&(_, n)| n.is_whitespace()) {
+                    end = i;
+                    break;
+                }
+            }
+            out.push(line[start + 1..end].to_string());
+        } else {
+            let mut end = line.len();
+            while let Some(&(i, d)) = chars.peek() {
+                if d.is_whitespace() {
+                    end = i;
+                    break;
+                }
+                chars.next();
+            }
+            out.push(line[start..end].to_string());
+        }
+    }
+}
+
+/// Numeric value without its standard uncertainty, e.g. `1.234(5)`.
+fn number(value: &str) -> Option<f32> {
+    value.split('(').next()?.parse().ok()
+}
+
+/// Element from a type symbol like `O2-` or, failing that, an atom label like `Cl1`.
+fn element(symbol: &str) -> Element {
+    let letters: String = symbol
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if letters.len() >= 2
+        && let Ok(e) = Element::from_letter(&letters[..2])
+    {
+        return e;
+    }
+    Element::from_letter(letters.get(..1).unwrap_or("")).unwrap_or(Element::Other)
+}
+
+impl Cif {
+    pub fn new(text: &str) -> io::Result<Self> {
+        let mut ident = String::new();
+        let mut items = HashMap::<String, String>::new();
+        let mut tables = Vec::<Table>::new();
+
+        // 先把文件切成单值项和 loop 表
+        let mut lines = text.lines().peekable();
+        let mut blocks = 0;
+        while let Some(raw) = lines.next() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("data_") {
+                blocks += 1;
+                if blocks > 1 {
+                    break;
+                }
+                ident = name.to_string();
+            } else if line.eq_ignore_ascii_case("loop_") {
+                let mut table = Table {
+                    tags: Vec::new(),
+                    rows: Vec::new(),
+                };
+                while let Some(tag) = lines
+                    .peek()
+                    .map(|l| l.trim())
+                    .filter(|l| l.starts_with('_'))
+                {
+                    table.tags.push(tag.to_string());
+                    lines.next();
+                }
+                let mut values = Vec::new();
+                while let Some(next) = lines.peek().map(|l| l.trim_end()) {
+                    let t = next.trim_start();
+                    if t.starts_with('_')
+                        || t.starts_with("data_")
+                        || t.eq_ignore_ascii_case("loop_")
+                    {
+                        break;
+                    }
+                    lines.next();
+                    if let Some(first) = next.strip_prefix(';') {
+                        values.push(read_text_field(first, &mut lines));
+                    } else {
+                        tokenize(next, &mut values);
+                    }
+                }
+                if !table.tags.is_empty() {
+                    table.rows = values
+                        .chunks_exact(table.tags.len())
+                        .map(|row| row.to_vec())
+                        .collect();
+                    tables.push(table);
+                }
+            } else if line.starts_with('_') {
+                let mut values = Vec::new();
+                tokenize(line, &mut values);
+                let tag = values.remove(0).to_lowercase();
+                let value = match values.into_iter().next() {
+                    Some(v) => v,
+                    None => match lines.next() {
+                        Some(next) => match next.strip_prefix(';') {
+                            Some(first) => read_text_field(first, &mut lines),
+                            None => {
+                                let mut v = Vec::new();
+                                tokenize(next, &mut v);
+                                v.into_iter().next().unwrap_or_default()
+                            }
+                        },
+                        None => String::new(),
+                    },
+                };
+                items.insert(tag, value);
+            }
+        }
+
+        let cell_item = |tag: &str| {
+            items
+                .get(tag)
+                .and_then(|v| number(v))
+                .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, format!("CIF missing {tag}")))
+        };
+        let cell = [
+            cell_item("_cell_length_a")?,
+            cell_item("_cell_length_b")?,
+            cell_item("_cell_length_c")?,
+            cell_item("_cell_angle_alpha")?,
+            cell_item("_cell_angle_beta")?,
+            cell_item("_cell_angle_gamma")?,
+        ];
+        let space_group = [
+            "_symmetry_space_group_name_h-m",
+            "_space_group_name_h-m_alt",
+        ]
+        .iter()
+        .find_map(|tag| items.get(*tag))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && v != "?" && v != ".");
+
+        let frac_to_cart = cell_matrix(cell);
+        let atom_table = tables
+            .iter()
+            .find(|t| t.column("_atom_site_fract_x").is_some())
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "CIF has no _atom_site loop"))?;
+        let c_label = atom_table.column("_atom_site_label");
+        let c_symbol = atom_table.column("_atom_site_type_symbol");
+        let c_occ = atom_table.column("_atom_site_occupancy");
+        let c_frac = [
+            "_atom_site_fract_x",
+            "_atom_site_fract_y",
+            "_atom_site_fract_z",
+        ]
+        .map(|tag| atom_table.column(tag));
+
+        let aniso = aniso_tensors(&tables, frac_to_cart);
+
+        let mut atoms = Vec::new();
+        for (i, row) in atom_table.rows.iter().enumerate() {
+            let get = |c: Option<usize>| c.and_then(|c| row.get(c)).map(String::as_str);
+            let [Some(x), Some(y), Some(z)] = c_frac.map(|c| get(c).and_then(number)) else {
+                continue;
+            };
+            let label = get(c_label).unwrap_or("");
+            atoms.push(AtomGeneric {
+                serial_number: i as u32 + 1,
+                posit: frac_to_cart * Vec3::new(x, y, z),
+                element: element(get(c_symbol).unwrap_or(label)),
+                type_in_res_general: Some(label.to_string()),
+                occupancy: get(c_occ).and_then(number),
+                hetero: true,
+                aniso: aniso.get(label).copied(),
+                ..Default::default()
+            });
+        }
+
+        Ok(Self {
+            ident,
+            cell,
+            space_group,
+            atoms,
+        })
+    }
+}
+
+/// Reads a `;`-delimited text field whose first line (after the `;`) is `first`.
+fn read_text_field<'a>(first: &str, lines: &mut impl Iterator<Item = &'a str>) -> String {
+    let mut text = first.to_string();
+    for line in lines.by_ref() {
+        if line.starts_with(';') {
+            break;
+        }
+        text.push('\n');
+        text.push_str(line);
+    }
+    text
+}
+
+/// Displacement tensors by atom label, converted to Cartesian Å².
+///
+/// CIF U values refer to the crystal axes scaled by the reciprocal lengths:
+/// U_cart = A N U N^T A^T, with A the cell matrix and N = diag(a*, b*, c*).
+fn aniso_tensors(tables: &[Table], frac_to_cart: Mat3) -> HashMap<String, [f32; 6]> {
+    let mut result = HashMap::new();
+    let Some(table) = tables
+        .iter()
+        .find(|t| t.column("_atom_site_aniso_label").is_some())
+    else {
+        return result;
+    };
+
+    // B = 8π² U
+    let (prefix, factor) = match table.column("_atom_site_aniso_u_11") {
+        Some(_) => ("_atom_site_aniso_u_", 1.0),
+        None => ("_atom_site_aniso_b_", 1.0 / (8.0 * PI * PI)),
+    };
+    let columns =
+        ["11", "22", "33", "12", "13", "23"].map(|ij| table.column(&format!("{prefix}{ij}")));
+    let c_label = table.column("_atom_site_aniso_label");
+
+    let inverse_t = frac_to_cart.inverse().transpose();
+    let n = Mat3::from_diagonal(Vec3::new(
+        inverse_t.x_axis.length(),
+        inverse_t.y_axis.length(),
+        inverse_t.z_axis.length(),
+    ));
+    let an = frac_to_cart * n;
+
+    for row in &table.rows {
+        let get = |c: Option<usize>| c.and_then(|c| row.get(c)).and_then(|v| number(v));
+        let (
+            Some(label),
+            [
+                Some(u11),
+                Some(u22),
+                Some(u33),
+                Some(u12),
+                Some(u13),
+                Some(u23),
+            ],
+        ) = (c_label.and_then(|c| row.get(c)), columns.map(get))
+        else {
+            continue;
+        };
+        let u = Mat3::from_cols(
+            Vec3::new(u11, u12, u13),
+            Vec3::new(u12, u22, u23),
+            Vec3::new(u13, u23, u33),
+        ) * factor;
+        let c = an * u * an.transpose();
+        result.insert(
+            label.clone(),
+            [
+                c.x_axis.x, c.y_axis.y, c.z_axis.z, c.y_axis.x, c.z_axis.x, c.z_axis.y,
+            ],
+        );
+    }
+    result
+}
@@ -0,0 +1,150 @@
+// Gaussian cube files
+//
+// https://paulbourke.net/dataformats/cube/
+
+use crate::parser::utils::AtomGeneric;
+use crate::shapes::VolumeGrid;
+use glam::Vec3;
+use na_seq::Element;
+use std::io;
+use std::io::ErrorKind;
+
+pub const BOHR_TO_ANGSTROM: f32 = 0.529_177_2;
+
+#[derive(Clone, Debug)]
+pub struct Cube {
+    /// The two comment lines at the top of the file.
+    pub comments: [String; 2],
+    /// Atom positions in Å.
+    pub atoms: Vec<AtomGeneric>,
+    /// One grid per data set, in Å. Usually one; several for multi-orbital files.
+    pub volumes: Vec<VolumeGrid>,
+    /// Orbital numbers of `volumes`, empty unless the file lists them.
+    pub orbitals: Vec<i32>,
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg.into())
+}
+
+fn parse_nums<T: std::str::FromStr>(line: &str, what: &str) -> io::Result<Vec<T>> {
+    line.split_whitespace()
+        .map(|s| {
+            s.parse::<T>()
+                .map_err(|_| invalid(format!("Invalid {what}: '{s}'")))
+        })
+        .collect()
+}
+
+impl Cube {
+    pub fn new(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines();
+        let mut next_line = |what: &str| {
+            lines
+                .next()
+                .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, format!("Missing {what}")))
+        };
+
+        let comments = [
+            next_line("comment line")?.to_string(),
+            next_line("comment line")?.to_string(),
+        ];
+
+        // 原子数为负时，原子之后还有一行轨道编号
+        let header: Vec<f32> = parse_nums(next_line("atom count line")?, "atom count line")?;
+        if header.len() < 4 {
+            return Err(invalid("Atom count line needs count and origin"));
+        }
+        let n_atoms_signed = header[0] as i32;
+        let n_atoms = n_atoms_signed.unsigned_abs() as usize;
+        let mut n_values = header.get(4).map_or(1, |&n| n.max(1.0) as usize);
+
+        let mut dims = [0usize; 3];
+        let mut axes = [Vec3::ZERO; 3];
+        // 格点数为负表示单位是 Å 而不是 bohr
+        let mut unit = BOHR_TO_ANGSTROM;
+        for d in 0..3 {
+            let cols: Vec<f32> = parse_nums(next_line("grid axis line")?, "grid axis line")?;
+            if cols.len() < 4 {
+                return Err(invalid("Grid axis line needs count and vector"));
+            }
+            if cols[0] < 0.0 {
+                unit = 1.0;
+            }
+            dims[d] = cols[0].abs() as usize;
+            axes[d] = Vec3::new(cols[1], cols[2], cols[3]);
+        }
+        let origin = Vec3::new(header[1], header[2], header[3]) * unit;
+        let axes = axes.map(|a| a * unit);
+
+        let mut atoms = Vec::with_capacity(n_atoms);
+        for i in 0..n_atoms {
+            let cols: Vec<f32> = parse_nums(next_line("atom line")?, "atom line")?;
+            if cols.len() < 5 {
+                return Err(invalid("Atom line needs Z, charge and position"));
+            }
+            atoms.push(AtomGeneric {
+                serial_number: i as u32 + 1,
+                posit: Vec3::new(cols[2], cols[3], cols[4]) * unit,
+                element: Element::from_atomic_number(cols[0] as u8).unwrap_or(Element::Other),
+                hetero: true,
+                ..Default::default()
+            });
+        }
+
+        // 剩余内容全部是数值；若有轨道编号行，先读出来
+        let mut rest: Vec<&str> = lines.flat_map(|l| l.split_whitespace()).collect();
+        let mut orbitals = Vec::new();
+        if n_atoms_signed < 0 {
+            let m = rest
+                .first()
+                .and_then(|s| s.parse::<usize>().ok())
+                .ok_or_else(|| invalid("Missing orbital count"))?;
+            orbitals = rest[1..=m.min(rest.len() - 1)]
+                .iter()
+                .map(|s| {
+                    s.parse()
+                        .map_err(|_| invalid(format!("Invalid orbital '{s}'")))
+                })
+                .collect::<io::Result<_>>()?;
+            rest.drain(..=m.min(rest.len() - 1));
+            n_values = m.max(1);
+        }
+
+        let n_points = dims[0] * dims[1] * dims[2];
+        if rest.len() < n_points * n_values {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "Expected {} values, found {}",
+                    n_points * n_values,
+                    rest.len()
+                ),
+            ));
+        }
+        let values: Vec<f32> = rest[..n_points * n_values]
+            .iter()
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| invalid(format!("Invalid value '{s}'")))
+            })
+            .collect::<io::Result<_>>()?;
+
+        // 文件中最后一个轴变化最快，而 VolumeGrid 要求第一个轴最快，所以把轴倒过来
+        let volumes = (0..n_values)
+            .map(|v| VolumeGrid {
+                dims: [dims[2], dims[1], dims[0]],
+                origin,
+                axes: [axes[2], axes[1], axes[0]],
+                values: values.iter().skip(v).step_by(n_values).copied().collect(),
+            })
+            .collect();
+
+        Ok(Self {
+            comments,
+            atoms,
+            volumes,
+            orbitals,
+        })
+    }
+}
@@ -10,7 +10,7 @@
 // Kabsch & Sander, Biopolymers 22:2577-2637 (1983)
 // https://doi.org/10.1002/bip.360221211
 
-use crate::parser::utils::{Residue, SecondaryStructure};
+use crate::parser::utils::{Residue, SecondaryStructure, dihedral_angle};
 use glam::Vec3;
 use kiddo::{KdTree, SquaredEuclidean};
 use na_seq::AminoAcid;
@@ -75,19 +75,40 @@ impl SecondaryStructureCalculator {
 
     /// 计算一条链的二级结构
     pub fn compute_secondary_structure(&self, residues: &[Residue]) -> Vec<SecondaryStructure> {
+        let hbonds = self.compute_hydrogen_bonds(residues);
+        self.compute_secondary_structure_from_hbonds(residues.len(), &hbonds)
+    }
+
+    /// 计算一条链的主链氢键列表
+    ///
+    /// `donor_idx` 为提供 N-H 的残基，`acceptor_idx` 为提供 C=O 的残基，
+    /// 两者均为 `residues` 中的下标；`energy` 单位为 kcal/mol。
+    pub fn compute_hydrogen_bonds(&self, residues: &[Residue]) -> Vec<HydrogenBond> {
         if residues.len() < 2 {
-            return vec![SecondaryStructure::Coil; residues.len()];
+            return Vec::new();
         }
 
         // 关键：先补氢
         let residues_with_h = self.add_imide_hydrogens(residues);
 
         // 关键：用加速版查找氢键
-        let hbonds = self.find_hydrogen_bonds(&residues_with_h);
+        self.find_hydrogen_bonds(&residues_with_h)
+    }
+
+    /// 由已计算好的氢键列表分配二级结构（避免重复计算氢键）
+    pub fn compute_secondary_structure_from_hbonds(
+        &self,
+        n_res: usize,
+        hbond_list: &[HydrogenBond],
+    ) -> Vec<SecondaryStructure> {
+        if n_res < 2 {
+            return vec![SecondaryStructure::Coil; n_res];
+        }
+
+        let hbonds = hbond_matrix(n_res, hbond_list);
 
         // 下面这些函数全部改为接受 &HBondMatrix
-        let mut flags = vec![0u32; residues.len()];
-        let n_res = residues.len();
+        let mut flags = vec![0u32; n_res];
 
         self.find_turns(3, &mut flags, &hbonds, n_res);
         self.mark_helices(3, &mut flags, n_res);
@@ -103,6 +124,72 @@ impl SecondaryStructureCalculator {
         self.assign_ss_labels(n_res, &helices, &strands, &mut flags)
     }
 
+    /// 只有 CA 坐标时的二级结构（P-SEA 的 CA 距离与虚拟二面角判据）
+    ///
+    /// Labesse et al., CABIOS 13:291-295 (1997)
+    /// https://doi.org/10.1093/bioinformatics/13.3.291
+    pub fn compute_secondary_structure_from_ca(&self, cas: &[Vec3]) -> Vec<SecondaryStructure> {
+        let n_res = cas.len();
+        let mut ss = vec![SecondaryStructure::Coil; n_res];
+        let d = |i: usize, j: usize| cas[i].distance(cas[j]);
+        let within = |v: f32, mean: f32, tol: f32| (v - mean).abs() <= tol;
+
+        // 螺旋：i..i+4 的 CA 距离和虚拟二面角都接近 α 螺旋
+        let mut helix = vec![false; n_res];
+        for i in 0..n_res.saturating_sub(4) {
+            let tau = dihedral_angle(cas[i], cas[i + 1], cas[i + 2], cas[i + 3]);
+            if within(d(i, i + 2), 5.5, 0.5)
+                && within(d(i, i + 3), 5.3, 0.5)
+                && within(d(i, i + 4), 6.4, 0.6)
+                && within(tau, 50.0, 20.0)
+            {
+                helix[i..=i + 4].fill(true);
+            }
+        }
+
+        // 链：i..i+3 伸展，虚拟二面角在 -170° ± 45° 附近
+        let mut strand = vec![false; n_res];
+        for i in 0..n_res.saturating_sub(3) {
+            let tau = dihedral_angle(cas[i], cas[i + 1], cas[i + 2], cas[i + 3]);
+            let tau = if tau > 0.0 { tau - 360.0 } else { tau };
+            if within(d(i, i + 2), 6.7, 0.6)
+                && within(d(i, i + 3), 9.9, 0.9)
+                && within(tau, -170.0, 45.0)
+            {
+                strand[i..=i + 3].fill(true);
+            }
+        }
+        // 伸展的残基还要和另一段伸展的残基配对才算 β 链
+        let paired: Vec<bool> = (0..n_res)
+            .map(|i| {
+                strand[i] && (0..n_res).any(|j| i.abs_diff(j) > 2 && strand[j] && d(i, j) <= 5.5)
+            })
+            .collect();
+
+        // 过短的片段当作 coil
+        let mut assign = |flags: &[bool], label: SecondaryStructure, min_len: usize| {
+            let mut i = 0;
+            while i < n_res {
+                let start = i;
+                while i < n_res && flags[i] {
+                    i += 1;
+                }
+                if i - start >= min_len {
+                    ss[start..i].fill(label);
+                }
+                i += 1;
+            }
+        };
+        assign(
+            &paired,
+            SecondaryStructure::Sheet,
+            self.min_strand_length + 1,
+        );
+        // 至少两个连续的螺旋窗口
+        assign(&helix, SecondaryStructure::Helix, self.min_helix_length + 3);
+        ss
+    }
+
     /// 补全缺失的亚氨基氢原子
     fn add_imide_hydrogens(&self, residues: &[Residue]) -> Vec<Residue> {
         let mut result = residues.to_vec();
@@ -162,9 +249,9 @@ impl SecondaryStructureCalculator {
         })
     }
 
-    fn find_hydrogen_bonds(&self, residues: &[Residue]) -> HBondMatrix {
+    fn find_hydrogen_bonds(&self, residues: &[Residue]) -> Vec<HydrogenBond> {
         let n = residues.len();
-        let mut matrix = vec![vec![false; n]; n];
+        let mut hbonds = Vec::new();
         let mut coords = Vec::with_capacity(n);
 
         // Step 1: 构建坐标缓存 + KDTree（只放 N 原子）
@@ -209,7 +296,11 @@ impl SecondaryStructureCalculator {
                     }
                     let energy = self.calc_hbond_energy(coords[i].c, coords[i].o, coords[j].n, h);
                     if energy < self.hbond_cutoff as f64 {
-                        matrix[j][i] = true;
+                        hbonds.push(HydrogenBond {
+                            donor_idx: j,
+                            acceptor_idx: i,
+                            energy: energy as f32,
+                        });
                     }
                 }
 
@@ -222,13 +313,17 @@ impl SecondaryStructureCalculator {
                     }
                     let energy = self.calc_hbond_energy(coords[j].c, coords[j].o, coords[i].n, h);
                     if energy < self.hbond_cutoff as f64 {
-                        matrix[i][j] = true;
+                        hbonds.push(HydrogenBond {
+                            donor_idx: i,
+                            acceptor_idx: j,
+                            energy: energy as f32,
+                        });
                     }
                 }
             }
         }
 
-        matrix
+        hbonds
     }
 
     #[inline]
@@ -694,6 +789,17 @@ impl SecondaryStructureCalculator {
     }
 }
 
+// hbonds[donor][acceptor] = true
+fn hbond_matrix(n_res: usize, hbond_list: &[HydrogenBond]) -> HBondMatrix {
+    let mut matrix = vec![vec![false; n_res]; n_res];
+    for hb in hbond_list {
+        if hb.donor_idx < n_res && hb.acceptor_idx < n_res {
+            matrix[hb.donor_idx][hb.acceptor_idx] = true;
+        }
+    }
+    matrix
+}
+
 #[inline]
 fn dist_sq(a: Point3, b: Point3) -> f64 {
     let dx = a[0] - b[0];
@@ -1,7 +1,8 @@
-use crate::parser::dssp::SecondaryStructureCalculator;
+use crate::parser::dssp::{HydrogenBond, SecondaryStructureCalculator};
 use crate::parser::utils::ResidueType;
 use crate::parser::utils::{
-    AtomGeneric, ChainGeneric, Residue, ResidueEnd, ResidueGeneric, SecondaryStructure,
+    AtomGeneric, BackboneDihedrals, ChainGeneric, MAX_CA_CA_DISTANCE, Residue, ResidueEnd,
+    ResidueGeneric, SecondaryStructure,
 };
 pub use crate::utils::{Logger, RustLogger};
 use glam::Vec3;
@@ -14,6 +15,7 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 use std::io;
 use std::io::ErrorKind;
+use std::ops::Range;
 use std::str::FromStr;
 
 use std::collections::HashMap;
@@ -30,6 +32,10 @@ pub struct MmCif {
     pub residues: Vec<ResidueGeneric>,
     pub secondary_structure: Vec<BackboneSS>,
     pub experimental_method: Option<ExperimentalMethod>,
+    /// Unit cell a, b, c (Å) and alpha, beta, gamma (degrees), from `_cell`.
+    pub cell: Option<[f32; 6]>,
+    /// Hermann-Mauguin space group symbol, from `_symmetry` or `_space_group`.
+    pub space_group: Option<String>,
 }
 
 impl MmCif {
@@ -46,6 +52,7 @@ impl MmCif {
         let mut chains = Vec::<ChainGeneric>::new();
         let mut res_idx = HashMap::<(String, u32), usize>::new();
         let mut chain_idx = HashMap::<String, usize>::new();
+        let mut aniso = HashMap::<u32, [f32; 6]>::new();
 
         let lines: Vec<&str> = text.lines().collect();
         let mut i = 0;
@@ -81,6 +88,53 @@ impl MmCif {
                     }
                 }
 
+                // 各向异性位移参数，按原子 id 对应，读完所有原子后再填入
+                if headers
+                    .first()
+                    .is_some_and(|h| h.starts_with("_atom_site_anisotrop."))
+                {
+                    let col = |tag: &str| headers.iter().position(|h| *h == tag);
+                    let columns = [
+                        "_atom_site_anisotrop.U[1][1]",
+                        "_atom_site_anisotrop.U[2][2]",
+                        "_atom_site_anisotrop.U[3][3]",
+                        "_atom_site_anisotrop.U[1][2]",
+                        "_atom_site_anisotrop.U[1][3]",
+                        "_atom_site_anisotrop.U[2][3]",
+                    ]
+                    .map(col);
+                    let c_id = col("_atom_site_anisotrop.id");
+
+                    while i < n {
+                        line = lines[i].trim();
+                        if line.is_empty()
+                            || line == "#"
+                            || line == "loop_"
+                            || line.starts_with('_')
+                        {
+                            break;
+                        }
+                        let fields: Vec<&str> = line.split_whitespace().collect();
+                        let value = |c: Option<usize>| -> Option<f32> {
+                            fields.get(c?)?.split('(').next()?.parse().ok()
+                        };
+                        if let Some(id) = c_id.and_then(|c| fields.get(c)?.parse::<u32>().ok())
+                            && let [
+                                Some(u11),
+                                Some(u22),
+                                Some(u33),
+                                Some(u12),
+                                Some(u13),
+                                Some(u23),
+                            ] = columns.map(value)
+                        {
+                            aniso.insert(id, [u11, u22, u33, u12, u13, u23]);
+                        }
+                        i += 1;
+                    }
+                    continue;
+                }
+
                 // If not an atom loops, skip first rows.
                 if !headers
                     .first()
@@ -113,6 +167,14 @@ impl MmCif {
                 let c_chain = col("_atom_site.label_asym_id")?;
                 let c_res_sn = col("_atom_site.label_seq_id")?;
                 let c_occ = col("_atom_site.occupancy")?;
+                let c_auth_seq = headers.iter().position(|h| *h == "_atom_site.auth_seq_id");
+                let c_auth_chain = headers.iter().position(|h| *h == "_atom_site.auth_asym_id");
+                let c_ins_code = headers
+                    .iter()
+                    .position(|h| *h == "_atom_site.pdbx_PDB_ins_code");
+                let c_b = headers
+                    .iter()
+                    .position(|h| *h == "_atom_site.B_iso_or_equiv");
 
                 while i < n {
                     line = lines[i].trim();
@@ -162,14 +224,29 @@ impl MmCif {
                         posit: Vec3::new(x as f32, y as f32, z as f32),
                         element,
                         type_in_res,
+                        type_in_res_general: Some(atom_name.to_string()),
                         occupancy: occ,
+                        b_factor: c_b.and_then(|c| fields[c].parse().ok()),
                         hetero,
                         alt_conformation_id,
                         ..Default::default()
                     });
 
                     // --------- Residue / Chain bookkeeping -----------
-                    let res_sn = fields[c_res_sn].parse::<u32>().unwrap_or(0);
+                    // 水和配体没有 label_seq_id，用 auth_seq_id 区分各个残基
+                    let res_sn = fields[c_res_sn].parse::<u32>().unwrap_or_else(|_| {
+                        c_auth_seq.and_then(|c| fields[c].parse().ok()).unwrap_or(0)
+                    });
+                    // 选择语言用作者编号（与 PDB 文件一致），缺失时才退回 label 编号
+                    let present = |c: Option<usize>| {
+                        c.map(|c| fields[c]).filter(|v| !matches!(*v, "?" | "."))
+                    };
+                    let res_seq = present(c_auth_seq)
+                        .and_then(|v| v.parse::<i32>().ok())
+                        .or_else(|| fields[c_res_sn].parse().ok())
+                        .unwrap_or(0);
+                    let ins_code = present(c_ins_code).unwrap_or("").to_string();
+                    let auth_chain = present(c_auth_chain).map(str::to_string);
                     let chain_id = fields[c_chain];
                     let res_key = (chain_id.to_string(), res_sn);
 
@@ -178,6 +255,9 @@ impl MmCif {
                         let idx = residues.len();
                         residues.push(ResidueGeneric {
                             serial_number: res_sn,
+                            res_seq,
+                            ins_code,
+                            auth_chain,
                             res_type: ResidueType::from_str(fields[c_res]),
                             atom_sns: Vec::new(),
                             end: ResidueEnd::Internal, // We update this after.
@@ -220,6 +300,10 @@ impl MmCif {
             i += 1; // advance to next top-level line
         }
 
+        for atom in &mut atoms {
+            atom.aniso = aniso.get(&atom.serial_number).copied();
+        }
+
         // Populate the residue end, now that we know when the last non-het one is.
         {
             let mut last_non_het = 0;
@@ -268,6 +352,31 @@ impl MmCif {
         // let ss_load_time = ss_load.elapsed();
         let secondary_structure = Vec::new();
 
+        // 晶胞参数可能带不确定度，如 "50.840(5)"
+        let number = |tag: &str| -> Option<f32> {
+            let v = metadata.get(tag)?;
+            v.split('(').next()?.trim().parse().ok()
+        };
+        let cell = (|| {
+            Some([
+                number("_cell.length_a")?,
+                number("_cell.length_b")?,
+                number("_cell.length_c")?,
+                number("_cell.angle_alpha")?,
+                number("_cell.angle_beta")?,
+                number("_cell.angle_gamma")?,
+            ])
+        })();
+
+        let space_group = [
+            "_symmetry.space_group_name_H-M",
+            "_space_group.name_H-M_alt",
+        ]
+        .iter()
+        .find_map(|tag| metadata.get(*tag))
+        .map(|v| v.trim_matches(['\'', '"']).trim().to_string())
+        .filter(|v| !v.is_empty() && v != "?" && v != ".");
+
         Ok(Self {
             ident,
             metadata,
@@ -276,6 +385,8 @@ impl MmCif {
             residues,
             secondary_structure,
             experimental_method,
+            cell,
+            space_group,
         })
     }
 }
@@ -380,21 +491,92 @@ pub struct Chain {
 
     #[serde(skip)]
     ss_cache: OnceCell<Vec<SecondaryStructure>>,
+    #[serde(skip)]
+    hbond_cache: OnceCell<Vec<HydrogenBond>>,
 }
 
 impl Chain {
+    /// Secondary structure per residue: the `ss` stored on the residues when all of
+    /// them have one, DSSP otherwise. Residues with approximated backbones take their
+    /// structure from CA geometry instead.
     pub fn get_ss(&self) -> &Vec<SecondaryStructure> {
         self.ss_cache.get_or_init(|| {
+            if let Some(ss) = self.residues.iter().map(|r| r.ss).collect() {
+                return ss;
+            }
             let calculator = SecondaryStructureCalculator::new();
-            calculator.compute_secondary_structure(&self.residues)
+            let approximated = self
+                .residues
+                .iter()
+                .filter(|r| r.approximate_backbone)
+                .count();
+            if approximated == 0 {
+                return calculator.compute_secondary_structure_from_hbonds(
+                    self.residues.len(),
+                    self.get_hbonds(),
+                );
+            }
+            let cas: Vec<Vec3> = self.residues.iter().map(|r| r.ca).collect();
+            let from_ca = calculator.compute_secondary_structure_from_ca(&cas);
+            if approximated == self.residues.len() {
+                return from_ca;
+            }
+            // 只有估计出主链的残基用 CA 规则，其余仍按 DSSP
+            let mut ss = calculator
+                .compute_secondary_structure_from_hbonds(self.residues.len(), self.get_hbonds());
+            for (i, residue) in self.residues.iter().enumerate() {
+                if residue.approximate_backbone {
+                    ss[i] = from_ca[i];
+                }
+            }
+            ss
         })
     }
 
+    /// Backbone hydrogen bonds of this chain, as found by DSSP. Donor and acceptor
+    /// are indices into `residues`.
+    pub fn get_hbonds(&self) -> &Vec<HydrogenBond> {
+        self.hbond_cache.get_or_init(|| {
+            let calculator = SecondaryStructureCalculator::new();
+            let mut hbonds = calculator.compute_hydrogen_bonds(&self.residues);
+            // 估计出来的 N/O 不参与氢键
+            hbonds.retain(|hb| {
+                !self.residues[hb.donor_idx].approximate_backbone
+                    && !self.residues[hb.acceptor_idx].approximate_backbone
+            });
+            hbonds
+        })
+    }
+
+    /// Phi/psi/omega of each residue, in the same order as `residues`.
+    pub fn get_dihedrals(&self) -> Vec<BackboneDihedrals> {
+        BackboneDihedrals::from_residues(&self.residues)
+    }
+
+    /// Unbroken stretches of `residues`. The chain breaks where consecutive CA atoms are
+    /// further apart than [`MAX_CA_CA_DISTANCE`] or residue numbers skip (missing loops).
+    pub fn get_segments(&self) -> Vec<Range<usize>> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for i in 1..=self.residues.len() {
+            let broken = self.residues.get(i).is_none_or(|next| {
+                let prev = &self.residues[i - 1];
+                next.sns > prev.sns + 1 || prev.ca.distance(next.ca) > MAX_CA_CA_DISTANCE
+            });
+            if broken {
+                segments.push(start..i);
+                start = i;
+            }
+        }
+        segments
+    }
+
     pub fn new(id: String, residues: Vec<Residue>) -> Self {
         Self {
             id,
             residues,
             ss_cache: OnceCell::new(), // 初始化私有缓存
+            hbond_cache: OnceCell::new(),
         }
     }
 }
@@ -1,4 +1,12 @@
+pub mod ccp4;
+pub mod cif;
+pub mod cube;
 pub mod dssp;
 pub mod mmcif;
+pub mod obj;
+pub mod pdb;
+pub mod ply;
+pub mod sasa;
 pub mod sdf;
+pub mod stl;
 pub mod utils;
@@ -0,0 +1,109 @@
+// Wavefront OBJ meshes
+//
+// https://paulbourke.net/dataformats/obj/
+
+use crate::utils::MeshData;
+use glam::{Vec3, Vec4};
+use std::collections::HashMap;
+use std::io;
+use std::io::ErrorKind;
+
+fn invalid(line: usize, msg: impl std::fmt::Display) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, format!("Line {line}: {msg}"))
+}
+
+/// Reads the geometry of an OBJ file; polygons are fan-triangulated.
+///
+/// Materials and texture coordinates are ignored. Per-vertex colors written as
+/// `v x y z r g b` are kept. Normals are left empty when the file has none.
+pub fn parse_obj(text: &str) -> io::Result<MeshData> {
+    let mut posits = Vec::new();
+    let mut colors = Vec::new();
+    let mut normals = Vec::new();
+
+    let mut mesh = MeshData::default();
+    let mut mesh_colors = Vec::new();
+    let mut has_normals = true;
+    // (位置索引, 法线索引) -> 输出顶点
+    let mut vertex_ids: HashMap<(usize, Option<usize>), u32> = HashMap::new();
+
+    for (n, line) in text.lines().enumerate() {
+        let n = n + 1;
+        let mut cols = line.split_whitespace();
+        let Some(tag) = cols.next() else {
+            continue;
+        };
+        let nums = |cols: std::str::SplitWhitespace| -> io::Result<Vec<f32>> {
+            cols.map(|s| {
+                s.parse::<f32>()
+                    .map_err(|_| invalid(n, format!("invalid number '{s}'")))
+            })
+            .collect()
+        };
+
+        match tag {
+            "v" => {
+                let v = nums(cols)?;
+                if v.len() < 3 {
+                    return Err(invalid(n, "vertex needs x, y, z"));
+                }
+                posits.push(Vec3::new(v[0], v[1], v[2]));
+                colors.push((v.len() >= 6).then(|| Vec4::new(v[3], v[4], v[5], 1.0)));
+            }
+            "vn" => {
+                let v = nums(cols)?;
+                if v.len() < 3 {
+                    return Err(invalid(n, "normal needs x, y, z"));
+                }
+                normals.push(Vec3::new(v[0], v[1], v[2]).normalize_or_zero());
+            }
+            "f" => {
+                // 索引从 1 开始，负数表示相对末尾
+                let resolve = |s: &str, len: usize| -> io::Result<usize> {
+                    let i: i64 = s
+                        .parse()
+                        .map_err(|_| invalid(n, format!("invalid index '{s}'")))?;
+                    let i = if i < 0 { len as i64 + i } else { i - 1 };
+                    if i < 0 || i >= len as i64 {
+                        return Err(invalid(n, format!("index {s} out of range")));
+                    }
+                    Ok(i as usize)
+                };
+
+                let mut face = Vec::new();
+                for corner in cols {
+                    let mut parts = corner.split('/');
+                    let v = resolve(parts.next().unwrap_or_default(), posits.len())?;
+                    let vn = match parts.nth(1) {
+                        Some(s) if !s.is_empty() => Some(resolve(s, normals.len())?),
+                        _ => None,
+                    };
+                    has_normals &= vn.is_some();
+
+                    let id = *vertex_ids.entry((v, vn)).or_insert_with(|| {
+                        mesh.vertices.push(posits[v]);
+                        mesh.normals.push(vn.map_or(Vec3::ZERO, |i| normals[i]));
+                        mesh_colors.push(colors[v].unwrap_or(Vec4::ONE));
+                        mesh.vertices.len() as u32 - 1
+                    });
+                    face.push(id);
+                }
+                if face.len() < 3 {
+                    return Err(invalid(n, "face needs at least 3 vertices"));
+                }
+                for i in 1..face.len() - 1 {
+                    mesh.indices.extend([face[0], face[i], face[i + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !has_normals {
+        mesh.normals.clear();
+    }
+    if colors.iter().any(Option::is_some) {
+        mesh.colors = Some(mesh_colors);
+    }
+    Ok(mesh)
+}
@@ -0,0 +1,189 @@
+// PDB coordinate files (ATOM, HETATM, ANISOU and CRYST1 records)
+//
+// https://www.wwpdb.org/documentation/file-format-content/format33/v3.3.html
+
+use crate::parser::utils::{AtomGeneric, ChainGeneric, ResidueEnd, ResidueGeneric, ResidueType};
+use glam::Vec3;
+use na_seq::{AtomTypeInRes, Element};
+use std::collections::HashMap;
+use std::io;
+use std::io::ErrorKind;
+use std::str::FromStr;
+
+#[derive(Clone, Debug)]
+pub struct Pdb {
+    pub ident: String,
+    /// Atoms of the first model.
+    pub atoms: Vec<AtomGeneric>,
+    pub chains: Vec<ChainGeneric>,
+    pub residues: Vec<ResidueGeneric>,
+    /// Unit cell a, b, c (Å) and alpha, beta, gamma (degrees), from CRYST1.
+    pub cell: Option<[f32; 6]>,
+    pub space_group: Option<String>,
+}
+
+/// Fixed-width column range, 1-based and inclusive as in the format description.
+fn column(line: &str, start: usize, end: usize) -> &str {
+    line.get(start - 1..end.min(line.len()))
+        .unwrap_or("")
+        .trim()
+}
+
+fn invalid(line_no: usize, msg: impl Into<String>) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("Line {}: {}", line_no + 1, msg.into()),
+    )
+}
+
+impl Pdb {
+    pub fn new(text: &str) -> io::Result<Self> {
+        let mut ident = String::new();
+        let mut atoms = Vec::new();
+        let mut cell = None;
+        let mut space_group = None;
+        let mut aniso = HashMap::<u32, [f32; 6]>::new();
+        let mut chains = Vec::<ChainGeneric>::new();
+        let mut residues = Vec::<ResidueGeneric>::new();
+        let mut res_idx = HashMap::<(String, i32, String), usize>::new();
+
+        for (n, line) in text.lines().enumerate() {
+            let record = column(line, 1, 6);
+            match record {
+                "HEADER" => ident = column(line, 63, 66).to_string(),
+                "CRYST1" => {
+                    let params = [(7, 15), (16, 24), (25, 33), (34, 40), (41, 47), (48, 54)]
+                        .map(|(a, b)| column(line, a, b).parse::<f32>());
+                    if let [Ok(a), Ok(b), Ok(c), Ok(alpha), Ok(beta), Ok(gamma)] = params {
+                        cell = Some([a, b, c, alpha, beta, gamma]);
+                    }
+                    let sg = column(line, 56, 66);
+                    if !sg.is_empty() {
+                        space_group = Some(sg.to_string());
+                    }
+                }
+                "ATOM" | "HETATM" => {
+                    let coord = |a, b| {
+                        column(line, a, b)
+                            .parse::<f32>()
+                            .map_err(|_| invalid(n, "Invalid coordinate"))
+                    };
+                    let posit = Vec3::new(coord(31, 38)?, coord(39, 46)?, coord(47, 54)?);
+                    let name = column(line, 13, 16);
+                    let hetero = record == "HETATM";
+                    // 元素列缺失时从原子名推断：蛋白原子取首字母（CA 是 C-alpha），
+                    // 只有两个字母的 HETATM 名（ZN、MG 等离子）按整个名字匹配
+                    let element = match column(line, 77, 78) {
+                        "" => {
+                            let letters = name.trim_start_matches(|c: char| c.is_ascii_digit());
+                            let two_letter = (hetero && letters.len() == 2)
+                                .then(|| Element::from_letter(letters).ok())
+                                .flatten();
+                            two_letter.unwrap_or_else(|| {
+                                Element::from_letter(letters.get(..1).unwrap_or(""))
+                                    .unwrap_or(Element::Other)
+                            })
+                        }
+                        s => Element::from_letter(s).unwrap_or(Element::Other),
+                    };
+                    let alt = column(line, 17, 17);
+                    let serial_number = column(line, 7, 11).parse().unwrap_or(0);
+
+                    // 残基按 (链, 残基号, 插入码) 归并；serial_number 无符号，负的残基号记为 0
+                    let chain_id = column(line, 22, 22);
+                    let res_seq = column(line, 23, 26).parse::<i32>().unwrap_or(0);
+                    let res_sn = res_seq.max(0) as u32;
+                    let key = (
+                        chain_id.to_string(),
+                        res_seq,
+                        column(line, 27, 27).to_string(),
+                    );
+                    let r_i = *res_idx.entry(key).or_insert_with(|| {
+                        let res_type = ResidueType::from_str(column(line, 18, 20));
+                        residues.push(ResidueGeneric {
+                            serial_number: res_sn,
+                            res_seq,
+                            ins_code: column(line, 27, 27).to_string(),
+                            auth_chain: None,
+                            end: match res_type {
+                                ResidueType::AminoAcid(_) => ResidueEnd::Internal,
+                                _ => ResidueEnd::Hetero,
+                            },
+                            res_type,
+                            atom_sns: Vec::new(),
+                        });
+                        residues.len() - 1
+                    });
+                    residues[r_i].atom_sns.push(serial_number);
+
+                    let chain = match chains.iter_mut().position(|c| c.id == chain_id) {
+                        Some(c) => &mut chains[c],
+                        None => {
+                            chains.push(ChainGeneric {
+                                id: chain_id.to_string(),
+                                residue_sns: Vec::new(),
+                                atom_sns: Vec::new(),
+                            });
+                            chains.last_mut().unwrap()
+                        }
+                    };
+                    chain.atom_sns.push(serial_number);
+                    if !chain.residue_sns.contains(&res_sn) {
+                        chain.residue_sns.push(res_sn);
+                    }
+
+                    atoms.push(AtomGeneric {
+                        serial_number,
+                        posit,
+                        element,
+                        type_in_res: if hetero {
+                            Some(AtomTypeInRes::Hetero(name.to_string()))
+                        } else {
+                            AtomTypeInRes::from_str(name).ok()
+                        },
+                        type_in_res_general: Some(name.to_string()),
+                        hetero,
+                        occupancy: column(line, 55, 60).parse().ok(),
+                        b_factor: column(line, 61, 66).parse().ok(),
+                        alt_conformation_id: (!alt.is_empty()).then(|| alt.to_string()),
+                        ..Default::default()
+                    });
+                }
+                // ANISOU 以 10^-4 Å² 为单位存整数
+                "ANISOU" => {
+                    let u = [(29, 35), (36, 42), (43, 49), (50, 56), (57, 63), (64, 70)]
+                        .map(|(a, b)| column(line, a, b).parse::<i32>());
+                    if let (Ok(serial), [Ok(u11), Ok(u22), Ok(u33), Ok(u12), Ok(u13), Ok(u23)]) =
+                        (column(line, 7, 11).parse::<u32>(), u)
+                    {
+                        aniso.insert(
+                            serial,
+                            [u11, u22, u33, u12, u13, u23].map(|v| v as f32 * 1e-4),
+                        );
+                    }
+                }
+                "ENDMDL" => break,
+                _ => {}
+            }
+        }
+
+        if atoms.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "No ATOM or HETATM records",
+            ));
+        }
+        for atom in &mut atoms {
+            atom.aniso = aniso.get(&atom.serial_number).copied();
+        }
+
+        Ok(Self {
+            ident,
+            atoms,
+            chains,
+            residues,
+            cell,
+            space_group,
+        })
+    }
+}
@@ -0,0 +1,283 @@
+// Stanford PLY meshes, ASCII and binary
+//
+// https://paulbourke.net/dataformats/ply/
+
+use crate::shapes::MeshError;
+use crate::utils::MeshData;
+use glam::{Vec3, Vec4};
+use std::io;
+use std::io::ErrorKind;
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg.into())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> io::Result<Self> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(invalid(format!("Unknown PLY type '{name}'"))),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+struct Property {
+    name: String,
+    ty: Scalar,
+    /// Type of the length prefix for list properties.
+    list_len: Option<Scalar>,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Reads values from the body in either ASCII or binary encoding.
+struct Body<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Body<'_> {
+    fn read(&mut self, ty: Scalar) -> io::Result<f64> {
+        if self.format == Format::Ascii {
+            let rest = &self.bytes[self.pos..];
+            let start = rest
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+                .ok_or_else(|| {
+                    io::Error::new(ErrorKind::UnexpectedEof, "Unexpected end of PLY data")
+                })?;
+            let len = rest[start..]
+                .iter()
+                .position(|b| b.is_ascii_whitespace())
+                .unwrap_or(rest.len() - start);
+            let token = std::str::from_utf8(&rest[start..start + len]).unwrap_or_default();
+            self.pos += start + len;
+            return token
+                .parse()
+                .map_err(|_| invalid(format!("Invalid PLY value '{token}'")));
+        }
+
+        let size = ty.size();
+        let Some(raw) = self.bytes.get(self.pos..self.pos + size) else {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "Unexpected end of PLY data",
+            ));
+        };
+        self.pos += size;
+        let mut b = [0u8; 8];
+        b[..size].copy_from_slice(raw);
+        if self.format == Format::BinaryBigEndian {
+            b[..size].reverse();
+        }
+        Ok(match ty {
+            Scalar::I8 => b[0] as i8 as f64,
+            Scalar::U8 => b[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(b),
+        })
+    }
+}
+
+/// Reads the `vertex` and `face` elements of a PLY file; polygons are fan-triangulated.
+///
+/// Vertex normals (`nx ny nz`) and colors (`red green blue [alpha]`) are kept
+/// when present. Other elements are skipped.
+pub fn parse_ply(bytes: &[u8]) -> io::Result<MeshData> {
+    const END: &[u8] = b"end_header";
+    let header_end = bytes
+        .windows(END.len())
+        .position(|w| w == END)
+        .ok_or_else(|| invalid("Missing PLY end_header"))?;
+    let body_start = bytes[header_end..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |p| header_end + p + 1);
+    let header = std::str::from_utf8(&bytes[..header_end])
+        .map_err(|_| invalid("PLY header is not valid text"))?;
+
+    let mut lines = header.lines();
+    if lines.next().map(str::trim) != Some("ply") {
+        return Err(invalid("Not a PLY file"));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in lines {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        match cols.as_slice() {
+            ["format", f, ..] => {
+                format = Some(match *f {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(invalid(format!("Unknown PLY format '{f}'"))),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| invalid(format!("Invalid element count '{count}'")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", len_ty, ty, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid("Property before element"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    ty: Scalar::parse(ty)?,
+                    list_len: Some(Scalar::parse(len_ty)?),
+                });
+            }
+            ["property", ty, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid("Property before element"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    ty: Scalar::parse(ty)?,
+                    list_len: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let mut body = Body {
+        format: format.ok_or_else(|| invalid("Missing PLY format line"))?,
+        bytes,
+        pos: body_start,
+    };
+
+    let mut mesh = MeshData::default();
+    let mut colors = Vec::new();
+    for element in &elements {
+        let find = |name: &str| element.properties.iter().position(|p| p.name == name);
+        let [x, y, z, nx, ny, nz] = ["x", "y", "z", "nx", "ny", "nz"].map(find);
+        let [r, g, b, a] = ["red", "green", "blue", "alpha"].map(find);
+        let face_list = find("vertex_indices").or_else(|| find("vertex_index"));
+        // 整数颜色为 0-255，浮点颜色为 0-1
+        let color_scale = match r.map(|r| element.properties[r].ty) {
+            Some(Scalar::F32 | Scalar::F64) => 1.0,
+            _ => 1.0 / 255.0,
+        };
+
+        for _ in 0..element.count {
+            let mut values = Vec::with_capacity(element.properties.len());
+            let mut list = Vec::new();
+            for (i, property) in element.properties.iter().enumerate() {
+                match property.list_len {
+                    Some(len_ty) => {
+                        let len = body.read(len_ty)? as usize;
+                        let items = (0..len)
+                            .map(|_| body.read(property.ty))
+                            .collect::<io::Result<Vec<_>>>()?;
+                        if Some(i) == face_list {
+                            list = items;
+                        }
+                        values.push(0.0);
+                    }
+                    None => values.push(body.read(property.ty)?),
+                }
+            }
+
+            let get = |i: Option<usize>| i.map(|i| values[i] as f32);
+            match element.name.as_str() {
+                "vertex" => {
+                    let (Some(x), Some(y), Some(z)) = (get(x), get(y), get(z)) else {
+                        return Err(invalid("PLY vertex needs x, y, z"));
+                    };
+                    mesh.vertices.push(Vec3::new(x, y, z));
+                    if let (Some(nx), Some(ny), Some(nz)) = (get(nx), get(ny), get(nz)) {
+                        mesh.normals.push(Vec3::new(nx, ny, nz).normalize_or_zero());
+                    }
+                    if let (Some(r), Some(g), Some(b)) = (get(r), get(g), get(b)) {
+                        let alpha = get(a).unwrap_or(1.0 / color_scale);
+                        colors.push(Vec4::new(r, g, b, alpha) * color_scale);
+                    }
+                }
+                "face" => {
+                    // 索引按浮点读入，负数、小数和 NaN 不能直接截断成 u32
+                    let face = list
+                        .iter()
+                        .map(|&v| {
+                            if v >= 0.0 && v.fract() == 0.0 && v <= u32::MAX as f64 {
+                                Ok(v as u32)
+                            } else {
+                                Err(io::Error::new(
+                                    ErrorKind::InvalidData,
+                                    MeshError::InvalidIndex(v),
+                                ))
+                            }
+                        })
+                        .collect::<io::Result<Vec<_>>>()?;
+                    for i in 1..face.len().saturating_sub(1) {
+                        mesh.indices.extend([face[0], face[i], face[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if mesh
+        .indices
+        .iter()
+        .any(|&i| i as usize >= mesh.vertices.len())
+    {
+        return Err(invalid("PLY face index out of range"));
+    }
+    if mesh.normals.len() != mesh.vertices.len() {
+        mesh.normals.clear();
+    }
+    if !colors.is_empty() && colors.len() == mesh.vertices.len() {
+        mesh.colors = Some(colors);
+    }
+    Ok(mesh)
+}
@@ -0,0 +1,147 @@
+// Solvent accessible surface area (SASA)
+//
+// Shrake & Rupley, J. Mol. Biol. 79:351-371 (1973)
+// https://doi.org/10.1016/0022-2836(73)90011-9
+
+use crate::parser::mmcif::MmCif;
+use crate::parser::utils::AtomGeneric;
+use glam::Vec3;
+use kiddo::{KdTree, SquaredEuclidean};
+use na_seq::Element;
+use std::collections::HashMap;
+
+/// Shrake–Rupley SASA calculator. Areas are in Å².
+pub struct SasaCalculator {
+    /// Solvent probe radius, 1.4 Å for water.
+    pub probe_radius: f32,
+    /// Number of test points on each atom sphere.
+    pub n_points: usize,
+}
+
+impl Default for SasaCalculator {
+    fn default() -> Self {
+        Self {
+            probe_radius: 1.4,
+            n_points: 100,
+        }
+    }
+}
+
+impl SasaCalculator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Per-atom SASA for arbitrary spheres.
+    pub fn compute(&self, posits: &[Vec3], radii: &[f32]) -> Vec<f32> {
+        let n = posits.len().min(radii.len());
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let sphere = golden_spiral_points(self.n_points.max(1));
+        let expanded: Vec<f32> = radii[..n].iter().map(|r| r + self.probe_radius).collect();
+        let max_r = expanded.iter().copied().fold(0.0f32, f32::max);
+
+        let mut tree: KdTree<f32, 3> = KdTree::new();
+        for (i, p) in posits[..n].iter().enumerate() {
+            tree.add(&p.to_array(), i as u64);
+        }
+
+        let mut result = Vec::with_capacity(n);
+        let mut neighbors = Vec::new();
+        for i in 0..n {
+            let center = posits[i];
+            let r_i = expanded[i];
+            let cutoff = r_i + max_r;
+
+            // 只保留真正可能重叠的邻居
+            neighbors.clear();
+            for nb in tree.within_unsorted::<SquaredEuclidean>(&center.to_array(), cutoff * cutoff)
+            {
+                let j = nb.item as usize;
+                let r_j = expanded[j];
+                if j != i && nb.distance < (r_i + r_j) * (r_i + r_j) {
+                    neighbors.push((posits[j], r_j * r_j));
+                }
+            }
+
+            // 上一次遮挡当前点的邻居最可能继续遮挡下一个点
+            let mut last_hit = 0;
+            let mut accessible = 0usize;
+            for u in &sphere {
+                let p = center + *u * r_i;
+                if neighbors.is_empty() {
+                    accessible += 1;
+                    continue;
+                }
+                let (c, r_sq) = neighbors[last_hit];
+                if p.distance_squared(c) < r_sq {
+                    continue;
+                }
+                match neighbors
+                    .iter()
+                    .position(|(c, r_sq)| p.distance_squared(*c) < *r_sq)
+                {
+                    Some(k) => last_hit = k,
+                    None => accessible += 1,
+                }
+            }
+
+            let area = 4.0 * std::f32::consts::PI * r_i * r_i;
+            result.push(area * accessible as f32 / sphere.len() as f32);
+        }
+        result
+    }
+
+    /// Per-atom SASA using van der Waals radii of the atom elements.
+    pub fn compute_atoms(&self, atoms: &[AtomGeneric]) -> Vec<f32> {
+        let posits: Vec<Vec3> = atoms.iter().map(|a| a.posit).collect();
+        let radii: Vec<f32> = atoms.iter().map(|a| sasa_radius(&a.element)).collect();
+        self.compute(&posits, &radii)
+    }
+
+    /// Per-residue SASA, in the same order as `mmcif.residues`.
+    pub fn compute_residues(&self, mmcif: &MmCif) -> Vec<f32> {
+        let atom_sasa = self.compute_atoms(&mmcif.atoms);
+        let sn_to_idx: HashMap<u32, usize> = mmcif
+            .atoms
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.serial_number, i))
+            .collect();
+
+        mmcif
+            .residues
+            .iter()
+            .map(|res| {
+                res.atom_sns
+                    .iter()
+                    .filter_map(|sn| sn_to_idx.get(sn))
+                    .map(|&i| atom_sasa[i])
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// Van der Waals radius used for SASA; falls back to 1.8 Å for elements without one.
+pub fn sasa_radius(element: &Element) -> f32 {
+    match element.vdw_radius() {
+        r if r > 0.0 => r,
+        _ => 1.8,
+    }
+}
+
+/// Roughly uniform points on the unit sphere.
+fn golden_spiral_points(n: usize) -> Vec<Vec3> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..n)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+            let r = (1.0 - y * y).sqrt();
+            let theta = golden_angle * i as f32;
+            Vec3::new(r * theta.cos(), y, r * theta.sin())
+        })
+        .collect()
+}
@@ -232,6 +232,9 @@ impl Sdf {
 
         let residues = vec![ResidueGeneric {
             serial_number: 0,
+            res_seq: 0,
+            ins_code: String::new(),
+            auth_chain: None,
             res_type: ResidueType::Other("Unknown".to_string()),
             atom_sns: atom_sns.clone(),
             end: ResidueEnd::Hetero,
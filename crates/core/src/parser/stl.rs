@@ -0,0 +1,116 @@
+// STL meshes, ASCII and binary
+//
+// https://www.fabbers.com/tech/STL_Format
+
+use crate::utils::MeshData;
+use glam::Vec3;
+use std::io;
+use std::io::ErrorKind;
+
+/// Reads an ASCII or binary STL file.
+///
+/// STL stores every facet separately, so vertices are not shared and each
+/// carries its facet normal (recomputed from the winding when missing).
+pub fn parse_stl(bytes: &[u8]) -> io::Result<MeshData> {
+    // 二进制文件头也可能以 "solid" 开头，所以先按长度判断
+    let is_binary = bytes.len() >= 84 && {
+        let n = u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize;
+        // wasm32 上 usize 只有 32 位，面数很大时乘法会溢出
+        n.checked_mul(50).and_then(|b| b.checked_add(84)) == Some(bytes.len())
+    };
+    let facets = if is_binary {
+        binary_facets(bytes)
+    } else {
+        ascii_facets(bytes)?
+    };
+
+    let mut mesh = MeshData::default();
+    for (normal, corners) in facets {
+        let face_normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+        let normal = if normal.length_squared() > 0.0 {
+            normal.normalize()
+        } else {
+            face_normal.normalize_or_zero()
+        };
+        // 有的导出器绕向与法线不一致，以法线为准
+        let corners = if face_normal.dot(normal) < 0.0 {
+            [corners[0], corners[2], corners[1]]
+        } else {
+            corners
+        };
+        let base = mesh.vertices.len() as u32;
+        mesh.vertices.extend(corners);
+        mesh.normals.extend([normal; 3]);
+        mesh.indices.extend([base, base + 1, base + 2]);
+    }
+    Ok(mesh)
+}
+
+fn binary_facets(bytes: &[u8]) -> Vec<(Vec3, [Vec3; 3])> {
+    let vec3 = |b: &[u8]| {
+        let f = |i: usize| f32::from_le_bytes(b[i * 4..i * 4 + 4].try_into().unwrap());
+        Vec3::new(f(0), f(1), f(2))
+    };
+    bytes[84..]
+        .chunks_exact(50)
+        .map(|c| {
+            (
+                vec3(&c[0..12]),
+                [vec3(&c[12..24]), vec3(&c[24..36]), vec3(&c[36..48])],
+            )
+        })
+        .collect()
+}
+
+fn ascii_facets(bytes: &[u8]) -> io::Result<Vec<(Vec3, [Vec3; 3])>> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|_| io::Error::new(ErrorKind::InvalidData, "Not a valid STL file"))?;
+    if !text.trim_start().starts_with("solid") {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "Not a valid STL file",
+        ));
+    }
+
+    let parse_vec3 = |n: usize, cols: &[&str]| -> io::Result<Vec3> {
+        let v: Vec<f32> = cols
+            .iter()
+            .map(|s| s.parse::<f32>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| {
+                io::Error::new(ErrorKind::InvalidData, format!("Line {n}: invalid number"))
+            })?;
+        if v.len() != 3 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Line {n}: expected 3 coordinates"),
+            ));
+        }
+        Ok(Vec3::new(v[0], v[1], v[2]))
+    };
+
+    let mut facets = Vec::new();
+    let mut normal = Vec3::ZERO;
+    let mut corners = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        match cols.as_slice() {
+            ["facet", "normal", rest @ ..] => {
+                normal = parse_vec3(n + 1, rest)?;
+                corners.clear();
+            }
+            ["vertex", rest @ ..] => corners.push(parse_vec3(n + 1, rest)?),
+            ["endfacet", ..] => {
+                if corners.len() != 3 {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Line {}: facet needs 3 vertices", n + 1),
+                    ));
+                }
+                facets.push((normal, [corners[0], corners[1], corners[2]]));
+            }
+            _ => {}
+        }
+    }
+    Ok(facets)
+}
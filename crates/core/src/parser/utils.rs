@@ -44,9 +44,13 @@ pub struct AtomGeneric {
     /// ligands.
     pub hetero: bool,
     pub occupancy: Option<f32>,
+    /// Isotropic displacement (temperature) factor in Å².
+    pub b_factor: Option<f32>,
     /// Used by mmCIF files to store alternate conformations. If this isn't None, there may
     /// be, for example, an "A" and "B" variant of this atom at slightly different positions.
     pub alt_conformation_id: Option<String>,
+    /// Anisotropic displacement tensor in Cartesian Å², as U11, U22, U33, U12, U13, U23.
+    pub aniso: Option<[f32; 6]>,
 }
 
 impl Display for AtomGeneric {
@@ -138,6 +142,26 @@ impl ResidueType {
     }
 }
 
+mod atomic_number_serde {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S>(element: &Element, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_u8(element.atomic_number())
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Element, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let num = u8::deserialize(d)?;
+        Ok(Element::from_atomic_number(num).unwrap_or(Element::Other))
+    }
+}
+
 mod aa_serde {
     use super::*;
     use serde::{Deserialize, Deserializer, Serializer};
@@ -164,6 +188,12 @@ pub struct ResidueGeneric {
     /// We use serial number of display, search etc, and array index to select. Residue serial number is not
     /// unique in the molecule; only in the chain.
     pub serial_number: u32,
+    /// Author residue number (`auth_seq_id`, PDB `resSeq`), which may be negative.
+    pub res_seq: i32,
+    /// Author insertion code (`pdbx_PDB_ins_code`, PDB `iCode`), empty when there is none.
+    pub ins_code: String,
+    /// Author chain id (`auth_asym_id`); `None` when the file only has one chain id.
+    pub auth_chain: Option<String>,
     pub res_type: ResidueType,
     /// Serial number
     pub atom_sns: Vec<u32>,
@@ -327,6 +357,119 @@ pub struct Residue {
 
     // Secondary structure tag
     pub ss: Option<SecondaryStructure>,
+
+    /// B-factor of the CA atom, if the file has one.
+    #[serde(default)]
+    pub b_factor: Option<f32>,
+
+    /// N, C or O was missing (CA-only or coarse-grained models) and was placed from
+    /// the neighbouring CA atoms; such residues take no part in hydrogen bonds.
+    #[serde(default)]
+    pub approximate_backbone: bool,
+
+    /// All atoms of the residue, backbone included, for side chains and selections.
+    #[serde(default)]
+    pub atoms: Vec<ResidueAtom>,
+
+    /// Author residue number, insertion code and chain id, as used in publications
+    /// and by selections; `sns` and the chain id may follow the label numbering.
+    #[serde(default)]
+    pub auth_seq: Option<i32>,
+    #[serde(default)]
+    pub ins_code: String,
+    #[serde(default)]
+    pub auth_chain: Option<String>,
+}
+
+impl Residue {
+    /// Backbone atoms (N, CA, C, O) actually present in the file. Guide points placed
+    /// for [`approximate_backbone`](Self::approximate_backbone) residues are left out.
+    pub fn real_backbone(&self) -> Vec<(Vec3, Element)> {
+        if !self.approximate_backbone {
+            return vec![
+                (self.n, Element::Nitrogen),
+                (self.ca, Element::Carbon),
+                (self.c, Element::Carbon),
+                (self.o, Element::Oxygen),
+            ];
+        }
+        let mut atoms = vec![(self.ca, Element::Carbon)];
+        atoms.extend(
+            self.atoms
+                .iter()
+                .filter(|a| matches!(a.name.as_str(), "N" | "C" | "O"))
+                .map(|a| (a.posit, a.element)),
+        );
+        atoms
+    }
+}
+
+/// A named atom of a [`Residue`], e.g. `CB` or `OG1`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResidueAtom {
+    pub name: String,
+    #[serde(with = "atomic_number_serde")]
+    pub element: Element,
+    pub posit: Vec3,
+}
+
+/// Backbone torsion angles of one residue, in degrees. Angles that need a missing or
+/// non-bonded neighbouring residue (chain ends, breaks) are `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct BackboneDihedrals {
+    /// C(i-1) - N - CA - C
+    pub phi: Option<f32>,
+    /// N - CA - C - N(i+1)
+    pub psi: Option<f32>,
+    /// CA - C - N(i+1) - CA(i+1)
+    pub omega: Option<f32>,
+}
+
+/// Longest C-N distance (Å) still treated as a peptide bond.
+pub const MAX_PEPTIDE_BOND_LENGTH: f32 = 2.0;
+
+/// Longest CA-CA distance (Å) between consecutive residues of one chain segment.
+pub const MAX_CA_CA_DISTANCE: f32 = 4.2;
+
+impl BackboneDihedrals {
+    /// Computes phi/psi/omega for every residue of a chain, in order.
+    pub fn from_residues(residues: &[Residue]) -> Vec<Self> {
+        let bonded = |prev: &Residue, next: &Residue| {
+            !prev.approximate_backbone
+                && !next.approximate_backbone
+                && prev.c.distance(next.n) <= MAX_PEPTIDE_BOND_LENGTH
+        };
+
+        (0..residues.len())
+            .map(|i| {
+                let res = &residues[i];
+                let prev = i.checked_sub(1).map(|j| &residues[j]);
+                let next = residues.get(i + 1);
+
+                let phi = prev
+                    .filter(|p| bonded(p, res))
+                    .map(|p| dihedral_angle(p.c, res.n, res.ca, res.c));
+                let next = next.filter(|n| bonded(res, n));
+                let psi = next.map(|n| dihedral_angle(res.n, res.ca, res.c, n.n));
+                let omega = next.map(|n| dihedral_angle(res.ca, res.c, n.n, n.ca));
+
+                Self { phi, psi, omega }
+            })
+            .collect()
+    }
+}
+
+/// Dihedral angle a-b-c-d in degrees, in (-180, 180].
+pub fn dihedral_angle(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> f32 {
+    let b0 = a - b;
+    let b1 = (c - b).normalize_or_zero();
+    let b2 = d - c;
+
+    let v = b0 - b1 * b0.dot(b1);
+    let w = b2 - b1 * b2.dot(b1);
+    let x = v.dot(w);
+    let y = b1.cross(v).dot(w);
+    y.atan2(x).to_degrees()
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
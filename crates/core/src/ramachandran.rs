@@ -0,0 +1,186 @@
+use eframe::egui::{self, Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, StrokeKind, Vec2};
+use glam::{Mat4, Vec3, Vec4};
+use na_seq::AaIdent;
+
+use crate::Shape;
+use crate::parser::utils::SecondaryStructure;
+use crate::scene::Scene;
+
+const PLOT_SIZE: f32 = 260.0;
+const PICK_RADIUS: f32 = 8.0;
+const HIGHLIGHT_COLOR: Color32 = Color32::from_rgb(30, 200, 30);
+
+struct RamaPoint {
+    label: String,
+    /// CA position in scene units (already multiplied by `Scene::scale`).
+    ca: Vec3,
+    phi: f32,
+    psi: f32,
+    ss: SecondaryStructure,
+}
+
+/// Phi/psi scatter plot of all proteins in a scene, linked to the 3D view:
+/// hovering a residue in either place highlights it in both.
+pub(crate) struct RamachandranPlot {
+    points: Vec<RamaPoint>,
+    model: Mat4,
+    hovered: Option<usize>,
+}
+
+impl RamachandranPlot {
+    /// Returns `None` unless the scene asks for the panel.
+    pub fn from_scene(scene: &Scene) -> Option<Self> {
+        if !scene.show_ramachandran {
+            return None;
+        }
+
+        let mut points = Vec::new();
+        for shape in scene.named_shapes.values().chain(&scene.unnamed_shapes) {
+            let Shape::Protein(protein) = shape else {
+                continue;
+            };
+            for chain in &protein.chains {
+                let ss = chain.get_ss();
+                for (i, (res, d)) in chain.residues.iter().zip(chain.get_dihedrals()).enumerate() {
+                    let (Some(phi), Some(psi)) = (d.phi, d.psi) else {
+                        continue;
+                    };
+                    points.push(RamaPoint {
+                        label: format!(
+                            "{} {}{}",
+                            res.residue_type.to_str(AaIdent::ThreeLetters),
+                            chain.id,
+                            res.sns
+                        ),
+                        ca: res.ca * scene.scale,
+                        phi,
+                        psi,
+                        ss: ss.get(i).copied().unwrap_or(SecondaryStructure::Coil),
+                    });
+                }
+            }
+        }
+
+        Some(Self {
+            points,
+            model: scene.model_matrix(),
+            hovered: None,
+        })
+    }
+
+    /// Picks the front-most residue whose CA is under the pointer in the 3D view.
+    pub fn hover_3d(&mut self, view_proj: Mat4, rect: Rect, pointer: Pos2) {
+        let mvp = view_proj * self.model;
+        self.hovered = self
+            .points
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| project(mvp, rect, p.ca).map(|(pos, depth)| (i, pos, depth)))
+            .filter(|(_, pos, _)| pos.distance(pointer) < PICK_RADIUS)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(i, _, _)| i);
+    }
+
+    /// Marks the hovered residue on top of the 3D view.
+    pub fn paint_3d(&self, painter: &egui::Painter, view_proj: Mat4, rect: Rect) {
+        let Some(point) = self.hovered.and_then(|i| self.points.get(i)) else {
+            return;
+        };
+        let Some((pos, _)) = project(view_proj * self.model, rect, point.ca) else {
+            return;
+        };
+
+        painter.circle_stroke(pos, PICK_RADIUS, Stroke::new(2.0, HIGHLIGHT_COLOR));
+        painter.text(
+            pos + Vec2::new(PICK_RADIUS + 4.0, 0.0),
+            Align2::LEFT_CENTER,
+            format!("{}  φ {:.0}°  ψ {:.0}°", point.label, point.phi, point.psi),
+            FontId::proportional(13.0),
+            HIGHLIGHT_COLOR,
+        );
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Ramachandran")
+            .resizable(false)
+            .default_pos(Pos2::new(10.0, 10.0))
+            .show(ctx, |ui| {
+                let (response, painter) =
+                    ui.allocate_painter(Vec2::splat(PLOT_SIZE), Sense::hover());
+                let rect = response.rect;
+                let to_screen = |phi: f32, psi: f32| {
+                    Pos2::new(
+                        rect.left() + (phi + 180.0) / 360.0 * rect.width(),
+                        rect.top() + (180.0 - psi) / 360.0 * rect.height(),
+                    )
+                };
+
+                let axis = Stroke::new(1.0, Color32::from_gray(110));
+                painter.rect_filled(rect, 0.0, Color32::from_gray(24));
+                painter.line_segment([to_screen(0.0, -180.0), to_screen(0.0, 180.0)], axis);
+                painter.line_segment([to_screen(-180.0, 0.0), to_screen(180.0, 0.0)], axis);
+                painter.rect_stroke(rect, 0.0, axis, StrokeKind::Inside);
+
+                let font = FontId::proportional(12.0);
+                painter.text(
+                    rect.center_bottom() - Vec2::new(0.0, 4.0),
+                    Align2::CENTER_BOTTOM,
+                    "φ",
+                    font.clone(),
+                    Color32::GRAY,
+                );
+                painter.text(
+                    rect.left_center() + Vec2::new(4.0, 0.0),
+                    Align2::LEFT_CENTER,
+                    "ψ",
+                    font,
+                    Color32::GRAY,
+                );
+
+                if let Some(pointer) = response.hover_pos() {
+                    self.hovered = self
+                        .points
+                        .iter()
+                        .enumerate()
+                        .map(|(i, p)| (i, to_screen(p.phi, p.psi).distance(pointer)))
+                        .filter(|(_, d)| *d < PICK_RADIUS)
+                        .min_by(|a, b| a.1.total_cmp(&b.1))
+                        .map(|(i, _)| i);
+                }
+
+                for p in &self.points {
+                    painter.circle_filled(to_screen(p.phi, p.psi), 2.0, ss_color(p.ss));
+                }
+
+                if let Some(p) = self.hovered.and_then(|i| self.points.get(i)) {
+                    let pos = to_screen(p.phi, p.psi);
+                    painter.circle_filled(pos, 4.5, HIGHLIGHT_COLOR);
+                    ui.label(format!("{}  φ {:.1}°  ψ {:.1}°", p.label, p.phi, p.psi));
+                } else {
+                    ui.label("Hover a residue");
+                }
+            });
+    }
+}
+
+fn ss_color(ss: SecondaryStructure) -> Color32 {
+    match ss {
+        SecondaryStructure::Helix => Color32::from_rgb(230, 90, 160),
+        SecondaryStructure::Sheet => Color32::from_rgb(240, 190, 40),
+        _ => Color32::from_gray(170),
+    }
+}
+
+/// Model-space point → screen position and NDC depth; `None` behind the camera.
+fn project(mvp: Mat4, rect: Rect, p: Vec3) -> Option<(Pos2, f32)> {
+    let clip = mvp * Vec4::new(p.x, p.y, p.z, 1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.w;
+    let pos = Pos2::new(
+        rect.left() + (ndc.x + 1.0) * 0.5 * rect.width(),
+        rect.top() + (1.0 - ndc.y) * 0.5 * rect.height(),
+    );
+    Some((pos, ndc.z))
+}
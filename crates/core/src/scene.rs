@@ -2,6 +2,7 @@ use crate::utils::InstanceGroups;
 use crate::utils::Logger;
 use glam::Mat3;
 use glam::Mat4;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -29,6 +30,7 @@ pub struct Scene {
     pub viewport: Option<[usize; 2]>,
     pub scene_center: [f32; 3],
     pub camera_lights: Option<Lighting>,
+    pub show_ramachandran: bool,
     // pub _world_lights: Lighting,
 }
 
@@ -51,23 +53,40 @@ impl Default for Scene {
             viewport: None,
             scene_center: [0.0, 0.0, 0.0],
             camera_lights: None,
+            show_ramachandran: false,
         }
     }
 }
 
 impl Scene {
-    pub fn _get_meshes(&self) -> Vec<utils::MeshData> {
+    /// All shapes, with measurements that follow a molecule moved onto its current atoms.
+    pub(crate) fn resolved_shapes(&self) -> impl Iterator<Item = Cow<'_, Shape>> {
         self.named_shapes
             .values()
             .chain(self.unnamed_shapes.iter())
+            .map(|shape| match shape {
+                Shape::Measurement(m) => match m
+                    .molecule_id
+                    .as_ref()
+                    .and_then(|id| self.named_shapes.get(id))
+                {
+                    Some(Shape::Molecules(molecule)) => {
+                        Cow::Owned(Shape::Measurement(m.clone().update(molecule)))
+                    }
+                    _ => Cow::Borrowed(shape),
+                },
+                _ => Cow::Borrowed(shape),
+            })
+    }
+
+    pub fn _get_meshes(&self) -> Vec<utils::MeshData> {
+        self.resolved_shapes()
             .map(|s| s.to_mesh(self.scale))
             .collect()
     }
 
     pub fn get_instances_grouped(&self) -> InstanceGroups {
-        self.named_shapes
-            .values()
-            .chain(self.unnamed_shapes.iter())
+        self.resolved_shapes()
             .map(|s| s.to_instance_group(self.scale))
             .fold(InstanceGroups::default(), |mut acc, g| {
                 acc.merge(g);
@@ -137,6 +156,11 @@ impl Scene {
         self.unnamed_shapes.extend(other.unnamed_shapes.clone());
     }
 
+    /// Shows a phi/psi plot of the scene's proteins next to the 3D view.
+    pub fn show_ramachandran(&mut self, show: bool) {
+        self.show_ramachandran = show;
+    }
+
     pub fn add_camera_light(&mut self, light: Lighting) {
         self.camera_lights = Some(light);
     }
@@ -179,6 +203,7 @@ impl Interpolatable for Scene {
             viewport: self.viewport,
             scene_center: [scene_center.x, scene_center.y, scene_center.z],
             camera_lights: None,
+            show_ramachandran: self.show_ramachandran,
         }
     }
 }
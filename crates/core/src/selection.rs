@@ -0,0 +1,510 @@
+//! Atom selection expressions, e.g. `chain A and resi 10-20 and not hydrogen`
+//! or `byres (water and within 3.5 of resn LIG)`.
+//!
+//! Property terms take one or more values, separated by spaces or `+`:
+//! `elem`, `name`, `resn`, `chain` match strings (a trailing `*` matches a prefix),
+//! `resi` and `index` match numbers and ranges like `10-20`. `index` is the
+//! 0-based atom index. `resi` and `chain` follow the author numbering of PDB
+//! files, also for mmCIF input. Keywords: `all`, `none`, `hetero`, `water`, `hydrogen`,
+//! `protein`, `backbone`, `sidechain`. Operators, loosest first: `or`, `and`,
+//! then the prefixes `not`, `byres` and `within <Å> of`, which apply to the
+//! next term only; use parentheses for more.
+
+use glam::Vec3;
+use kiddo::{KdTree, SquaredEuclidean};
+use na_seq::{AaIdent, AminoAcid, Element};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::parser::mmcif::MmCif;
+use crate::parser::utils::{AtomGeneric, ChainGeneric, ResidueGeneric, ResidueType};
+use crate::shapes::Molecule;
+
+/// Residue and chain an atom belongs to, for selections and labels.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AtomSite {
+    /// Atom name within the residue, e.g. `CA` or `O1`.
+    pub name: String,
+    /// Upper-case residue name, e.g. `ALA` or `HOH`.
+    pub res_name: String,
+    /// Author residue number and insertion code, as in PDB files.
+    pub res_seq: i32,
+    pub ins_code: String,
+    /// Author chain id.
+    pub chain: String,
+    pub hetero: bool,
+}
+
+impl AtomSite {
+    /// Sites of `atoms`, looked up by serial number in the residues and chains of a
+    /// parsed structure file.
+    pub fn from_atoms(
+        atoms: &[AtomGeneric],
+        residues: &[ResidueGeneric],
+        chains: &[ChainGeneric],
+    ) -> Vec<Self> {
+        let mut residue_of = HashMap::new();
+        for res in residues {
+            for sn in &res.atom_sns {
+                residue_of.insert(*sn, res);
+            }
+        }
+        let mut chain_of = HashMap::new();
+        for chain in chains {
+            for sn in &chain.atom_sns {
+                chain_of.insert(*sn, chain.id.as_str());
+            }
+        }
+
+        atoms
+            .iter()
+            .map(|atom| {
+                let res = residue_of.get(&atom.serial_number);
+                Self {
+                    name: atom
+                        .type_in_res_general
+                        .clone()
+                        .or_else(|| atom.type_in_res.as_ref().map(|t| t.to_string()))
+                        .unwrap_or_default(),
+                    res_name: res
+                        .map(|r| match &r.res_type {
+                            ResidueType::AminoAcid(aa) => {
+                                aa.to_str(AaIdent::ThreeLetters).to_uppercase()
+                            }
+                            ResidueType::Water => "HOH".to_string(),
+                            ResidueType::Other(name) => name.to_uppercase(),
+                        })
+                        .unwrap_or_default(),
+                    res_seq: res.map_or(0, |r| r.res_seq),
+                    ins_code: res.map(|r| r.ins_code.clone()).unwrap_or_default(),
+                    chain: res
+                        .and_then(|r| r.auth_chain.clone())
+                        .or_else(|| chain_of.get(&atom.serial_number).map(|c| c.to_string()))
+                        .unwrap_or_default(),
+                    hetero: atom.hetero,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SelectionError {
+    #[error("Unexpected end of selection, expected {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("Unexpected '{0}' in selection")]
+    UnexpectedToken(String),
+    #[error("Invalid number '{0}' in selection")]
+    InvalidNumber(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Property {
+    Element,
+    Name,
+    ResName,
+    Chain,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    All,
+    None,
+    Hetero,
+    Water,
+    Hydrogen,
+    Protein,
+    Backbone,
+    Sidechain,
+    Match(Property, Vec<String>),
+    ResSeq(Vec<(i32, i32)>),
+    Index(Vec<(i32, i32)>),
+    Within(f32, Box<Expr>),
+    ByRes(Box<Expr>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+const KEYWORDS: [&str; 24] = [
+    "and",
+    "or",
+    "not",
+    "all",
+    "none",
+    "hetero",
+    "water",
+    "hydrogen",
+    "protein",
+    "backbone",
+    "sidechain",
+    "elem",
+    "element",
+    "name",
+    "resn",
+    "resname",
+    "resi",
+    "resid",
+    "chain",
+    "index",
+    "within",
+    "of",
+    "byres",
+    "(",
+];
+
+/// A parsed selection expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    expr: Expr,
+}
+
+impl FromStr for Selection {
+    type Err = SelectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Selection {
+    pub fn parse(text: &str) -> Result<Self, SelectionError> {
+        let tokens = tokenize(text);
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        match parser.next() {
+            Some(token) => Err(SelectionError::UnexpectedToken(token)),
+            None => Ok(Self { expr }),
+        }
+    }
+
+    /// Indices of the selected atoms, in ascending order. Residue terms match
+    /// nothing when `sites` is empty.
+    pub fn evaluate(
+        &self,
+        elements: &[Element],
+        posits: &[Vec3],
+        sites: &[AtomSite],
+    ) -> Vec<usize> {
+        let atoms = Atoms {
+            elements,
+            posits,
+            sites: (sites.len() == posits.len()).then_some(sites),
+        };
+        atoms
+            .mask(&self.expr)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, selected)| selected.then_some(i))
+            .collect()
+    }
+
+    /// Indices into `molecule.atom_posits`.
+    pub fn select_molecule(&self, molecule: &Molecule) -> Vec<usize> {
+        self.evaluate(
+            &molecule.atom_types,
+            &molecule.atom_posits,
+            molecule.atom_sites.as_deref().unwrap_or(&[]),
+        )
+    }
+
+    /// Indices into `mmcif.atoms`.
+    pub fn select_mmcif(&self, mmcif: &MmCif) -> Vec<usize> {
+        let elements: Vec<Element> = mmcif.atoms.iter().map(|a| a.element).collect();
+        let posits: Vec<Vec3> = mmcif.atoms.iter().map(|a| a.posit).collect();
+        let sites = AtomSite::from_atoms(&mmcif.atoms, &mmcif.residues, &mmcif.chains);
+        self.evaluate(&elements, &posits, &sites)
+    }
+}
+
+/// Splits on whitespace, keeping parentheses as separate tokens.
+fn tokenize(text: &str) -> Vec<String> {
+    text.replace('(', " ( ")
+        .replace(')', " ) ")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|t| t.eq_ignore_ascii_case(keyword))
+    }
+
+    fn or(&mut self) -> Result<Expr, SelectionError> {
+        let mut left = self.and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, SelectionError> {
+        let mut left = self.term()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.term()?));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, SelectionError> {
+        let token = self.next().ok_or(SelectionError::UnexpectedEnd("a term"))?;
+        let expr = match token.to_ascii_lowercase().as_str() {
+            "(" => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(t) if t == ")" => inner,
+                    Some(t) => return Err(SelectionError::UnexpectedToken(t)),
+                    None => return Err(SelectionError::UnexpectedEnd("')'")),
+                }
+            }
+            "not" => Expr::Not(Box::new(self.term()?)),
+            "byres" => Expr::ByRes(Box::new(self.term()?)),
+            "within" => {
+                let cutoff = self
+                    .next()
+                    .ok_or(SelectionError::UnexpectedEnd("a distance"))?;
+                let cutoff = cutoff
+                    .parse::<f32>()
+                    .map_err(|_| SelectionError::InvalidNumber(cutoff))?;
+                match self.next() {
+                    Some(t) if t.eq_ignore_ascii_case("of") => {}
+                    Some(t) => return Err(SelectionError::UnexpectedToken(t)),
+                    None => return Err(SelectionError::UnexpectedEnd("'of'")),
+                }
+                Expr::Within(cutoff, Box::new(self.term()?))
+            }
+            "all" | "*" => Expr::All,
+            "none" => Expr::None,
+            "hetero" | "hetatm" => Expr::Hetero,
+            "water" | "solvent" => Expr::Water,
+            "hydrogen" | "hydro" => Expr::Hydrogen,
+            "protein" => Expr::Protein,
+            "backbone" => Expr::Backbone,
+            "sidechain" => Expr::Sidechain,
+            "elem" | "element" => Expr::Match(Property::Element, self.values()?),
+            "name" => Expr::Match(Property::Name, self.values()?),
+            "resn" | "resname" => Expr::Match(Property::ResName, self.values()?),
+            "chain" => Expr::Match(Property::Chain, self.values()?),
+            "resi" | "resid" => Expr::ResSeq(self.ranges()?),
+            "index" => Expr::Index(self.ranges()?),
+            _ => return Err(SelectionError::UnexpectedToken(token)),
+        };
+        Ok(expr)
+    }
+
+    /// Values up to the next keyword or parenthesis; `A+B` counts as two values.
+    fn values(&mut self) -> Result<Vec<String>, SelectionError> {
+        let mut values = Vec::new();
+        while let Some(token) = self.peek() {
+            if token == ")" || KEYWORDS.iter().any(|k| token.eq_ignore_ascii_case(k)) {
+                break;
+            }
+            values.extend(
+                token
+                    .split('+')
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+            );
+            self.pos += 1;
+        }
+        if values.is_empty() {
+            return Err(match self.peek() {
+                Some(token) => SelectionError::UnexpectedToken(token.to_string()),
+                None => SelectionError::UnexpectedEnd("a value"),
+            });
+        }
+        Ok(values)
+    }
+
+    /// Numbers and inclusive ranges written `10-20` or `10:20`.
+    fn ranges(&mut self) -> Result<Vec<(i32, i32)>, SelectionError> {
+        self.values()?
+            .into_iter()
+            .map(|value| {
+                let invalid = || SelectionError::InvalidNumber(value.clone());
+                if let Ok(n) = value.parse::<i32>() {
+                    return Ok((n, n));
+                }
+                // 跳过首字符，允许负数起点，如 -5-10
+                let split = value
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(_, c)| c == '-' || c == ':')
+                    .map(|(i, _)| i)
+                    .ok_or_else(invalid)?;
+                let lo = value[..split].parse::<i32>().map_err(|_| invalid())?;
+                let hi = value[split + 1..].parse::<i32>().map_err(|_| invalid())?;
+                Ok((lo.min(hi), lo.max(hi)))
+            })
+            .collect()
+    }
+}
+
+struct Atoms<'a> {
+    elements: &'a [Element],
+    posits: &'a [Vec3],
+    sites: Option<&'a [AtomSite]>,
+}
+
+/// String match, case-insensitive except for chain ids, with a trailing `*` as prefix wildcard.
+fn matches(value: &str, pattern: &str, case_sensitive: bool) -> bool {
+    let eq = |a: &str, b: &str| {
+        if case_sensitive {
+            a == b
+        } else {
+            a.eq_ignore_ascii_case(b)
+        }
+    };
+    match pattern.strip_suffix('*') {
+        Some(prefix) => value
+            .get(..prefix.len())
+            .is_some_and(|head| eq(head, prefix)),
+        None => eq(value, pattern),
+    }
+}
+
+pub(crate) const WATER_NAMES: [&str; 6] = ["HOH", "WAT", "H2O", "DOD", "TIP3", "SOL"];
+const BACKBONE_NAMES: [&str; 4] = ["N", "CA", "C", "O"];
+
+impl Atoms<'_> {
+    fn site_mask(&self, f: impl Fn(&AtomSite) -> bool) -> Vec<bool> {
+        match self.sites {
+            Some(sites) => sites.iter().map(f).collect(),
+            None => vec![false; self.posits.len()],
+        }
+    }
+
+    fn mask(&self, expr: &Expr) -> Vec<bool> {
+        let n = self.posits.len();
+        // 只认三字母名，避免把 RNA 的 A、G、C、U 当成氨基酸
+        let is_protein = |s: &AtomSite| {
+            !s.hetero && s.res_name.len() == 3 && AminoAcid::from_str(&s.res_name).is_ok()
+        };
+        let is_backbone = |s: &AtomSite| BACKBONE_NAMES.contains(&s.name.as_str());
+        match expr {
+            Expr::All => vec![true; n],
+            Expr::None => vec![false; n],
+            Expr::Hetero => self.site_mask(|s| s.hetero),
+            Expr::Water => self.site_mask(|s| WATER_NAMES.contains(&s.res_name.as_str())),
+            Expr::Hydrogen => self
+                .elements
+                .iter()
+                .map(|e| *e == Element::Hydrogen)
+                .collect(),
+            Expr::Protein => self.site_mask(is_protein),
+            Expr::Backbone => self.site_mask(|s| is_protein(s) && is_backbone(s)),
+            Expr::Sidechain => self.site_mask(|s| is_protein(s) && !is_backbone(s)),
+            Expr::Match(Property::Element, values) => {
+                // 先按元素符号解析比较，na_seq 的 to_letter 并非每个元素都正确（如 Zn）
+                let parsed: Vec<Option<Element>> = values
+                    .iter()
+                    .map(|v| Element::from_letter(v).ok())
+                    .collect();
+                self.elements
+                    .iter()
+                    .map(|e| {
+                        values
+                            .iter()
+                            .zip(&parsed)
+                            .any(|(v, p)| *p == Some(*e) || matches(&e.to_letter(), v, false))
+                    })
+                    .collect()
+            }
+            Expr::Match(property, values) => self.site_mask(|s| {
+                let (value, case_sensitive) = match property {
+                    Property::Name => (&s.name, false),
+                    Property::ResName => (&s.res_name, false),
+                    _ => (&s.chain, true),
+                };
+                values.iter().any(|v| matches(value, v, case_sensitive))
+            }),
+            Expr::ResSeq(ranges) => self.site_mask(|s| {
+                ranges
+                    .iter()
+                    .any(|&(lo, hi)| (lo..=hi).contains(&s.res_seq))
+            }),
+            Expr::Index(ranges) => (0..n)
+                .map(|i| {
+                    ranges
+                        .iter()
+                        .any(|&(lo, hi)| (lo..=hi).contains(&(i as i32)))
+                })
+                .collect(),
+            Expr::Within(cutoff, inner) => {
+                let inner = self.mask(inner);
+                if !inner.contains(&true) {
+                    return vec![false; n];
+                }
+                let mut tree: KdTree<f32, 3> = KdTree::new();
+                for (i, p) in self.posits.iter().enumerate() {
+                    if inner[i] {
+                        tree.add(&p.to_array(), i as u64);
+                    }
+                }
+                self.posits
+                    .iter()
+                    .map(|p| {
+                        tree.nearest_one::<SquaredEuclidean>(&p.to_array()).distance
+                            <= cutoff * cutoff
+                    })
+                    .collect()
+            }
+            Expr::ByRes(inner) => {
+                let mut mask = self.mask(inner);
+                if let Some(sites) = self.sites {
+                    let key = |s: &'_ AtomSite| {
+                        (
+                            s.chain.clone(),
+                            s.res_seq,
+                            s.ins_code.clone(),
+                            s.res_name.clone(),
+                        )
+                    };
+                    let selected: HashSet<_> = sites
+                        .iter()
+                        .zip(&mask)
+                        .filter(|&(_, &m)| m)
+                        .map(|(s, _)| key(s))
+                        .collect();
+                    for (m, site) in mask.iter_mut().zip(sites) {
+                        *m = *m || selected.contains(&key(site));
+                    }
+                }
+                mask
+            }
+            Expr::Not(inner) => self.mask(inner).into_iter().map(|m| !m).collect(),
+            Expr::And(a, b) => self
+                .mask(a)
+                .into_iter()
+                .zip(self.mask(b))
+                .map(|(a, b)| a && b)
+                .collect(),
+            Expr::Or(a, b) => self
+                .mask(a)
+                .into_iter()
+                .zip(self.mask(b))
+                .map(|(a, b)| a || b)
+                .collect(),
+        }
+    }
+}
@@ -12,7 +12,10 @@ use eframe::{
 use glam::{Quat, Vec3};
 
 use crate::Scene;
+use crate::label_layer::LabelLayer;
+use crate::ramachandran::RamachandranPlot;
 use crate::scene::{Animation, Lighting};
+use crate::shapes::Cone;
 use crate::shapes::Sphere;
 use crate::shapes::SphereInstance;
 use crate::shapes::Stick;
@@ -27,19 +30,27 @@ pub struct Canvas<L: Logger> {
     interpolate_enabled: bool,
     animation_start_time: Option<f64>,
     last_frame_id: Option<usize>,
+    ramachandran: Option<RamachandranPlot>,
+    ramachandran_frame: Option<usize>,
+    labels: Option<LabelLayer>,
     logger: L,
 }
 
 impl<L: Logger> Canvas<L> {
     pub fn new(gl: Arc<eframe::glow::Context>, scene: &Scene, logger: L) -> Option<Self> {
         let camera_state = scene.camera_state.clone();
+        let shader = Shader::new(&gl, scene)?;
+        let labels = LabelLayer::from_scene(scene, shader.spheres());
         Some(Self {
-            shader: Arc::new(Mutex::new(Shader::new(&gl, scene)?)),
+            shader: Arc::new(Mutex::new(shader)),
             camera_state: camera_state.unwrap_or(CameraState::default()),
             animation: None,
             interpolate_enabled: false,
             animation_start_time: None,
             last_frame_id: None,
+            ramachandran: RamachandranPlot::from_scene(scene),
+            ramachandran_frame: None,
+            labels,
             logger,
         })
     }
@@ -54,9 +65,14 @@ impl<L: Logger> Canvas<L> {
         }
         let init_frame = &animation.frames[0];
         let camera_state = init_frame.camera_state;
+        let shader = Shader::new(&gl, init_frame)?;
+        let labels = LabelLayer::from_scene(init_frame, shader.spheres());
         Some(Self {
-            shader: Arc::new(Mutex::new(Shader::new(&gl, init_frame)?)),
+            shader: Arc::new(Mutex::new(shader)),
             camera_state: camera_state.unwrap_or(CameraState::default()),
+            ramachandran: RamachandranPlot::from_scene(init_frame),
+            ramachandran_frame: Some(0),
+            labels,
             interpolate_enabled: animation.interpolate,
             animation: Some(animation),
             animation_start_time: None,
@@ -136,7 +152,21 @@ impl<L: Logger> Canvas<L> {
                 }
             };
             if let Some(frame) = frame_to_render {
-                self.shader.lock().update_scene(Some(&frame), static_scene);
+                let mut shader = self.shader.lock();
+                shader.update_scene(Some(&frame), static_scene);
+                // 遮挡球直接复用刚上传的实例，不再重新生成
+                self.labels = LabelLayer::from_scene(&frame, shader.spheres());
+            }
+
+            // 面板跟随关键帧刷新，插值帧之间不重新计算二面角
+            let rama_frame = if is_finished {
+                frame_count - 1
+            } else {
+                frame_a_index
+            };
+            if Some(rama_frame) != self.ramachandran_frame {
+                self.ramachandran_frame = Some(rama_frame);
+                self.ramachandran = RamachandranPlot::from_scene(&animation.frames[rama_frame]);
             }
         }
         let scroll_delta = ui.input(|i| i.raw_scroll_delta.y);
@@ -170,10 +200,35 @@ impl<L: Logger> Canvas<L> {
             callback: Arc::new(cb),
         };
         ui.painter().add(callback);
+
+        if let Some(plot) = self.ramachandran.as_mut() {
+            let (view, projection, _) = self.camera_state.matrices(aspect_ratio);
+            let view_proj = projection * view;
+            if let Some(pointer) = response.hover_pos() {
+                plot.hover_3d(view_proj, rect, pointer);
+            }
+            plot.paint_3d(ui.painter(), view_proj, rect);
+        }
+
+        if let Some(labels) = self.labels.as_ref() {
+            let (view, projection, _) = self.camera_state.matrices(aspect_ratio);
+            labels.paint(ui.painter(), view, projection, rect);
+        }
+    }
+
+    /// Floating panels drawn on top of the 3D view.
+    pub fn show_panels(&mut self, ctx: &egui::Context) {
+        if let Some(plot) = self.ramachandran.as_mut() {
+            plot.show(ctx);
+        }
     }
 
     pub fn update_scene(&mut self, scene: &Scene) {
-        self.shader.lock().update_scene(Some(scene), None);
+        let mut shader = self.shader.lock();
+        shader.update_scene(Some(scene), None);
+        self.labels = LabelLayer::from_scene(scene, shader.spheres());
+        drop(shader);
+        self.ramachandran = RamachandranPlot::from_scene(scene);
     }
 }
 
@@ -185,21 +240,42 @@ struct Shader {
     vao_mesh: glow::VertexArray,
     vao_sphere: glow::VertexArray,
     vao_stick: glow::VertexArray,
+    vao_cone: glow::VertexArray,
     camera_lighting: Lighting,
     vertex3d: Vec<Vertex3d>,
     indices: Vec<u32>,
+    /// Indices before this offset belong to opaque meshes, the rest are drawn blended.
+    transparent_index_start: usize,
+    /// Wireframe edges and line segments, drawn as `GL_LINES` batch by batch.
+    line_indices: Vec<u32>,
+    line_batches: Vec<LineBatch>,
+    /// Line widths supported by the driver; often only 1.0 on core profiles and WebGL.
+    line_width_range: [f32; 2],
     sphere_index_count: usize,
     stick_index_count: usize,
+    cone_index_count: usize,
     background_color: [f32; 3],
     vbo: glow::Buffer,
     ebo: glow::Buffer,
+    ebo_lines: glow::Buffer,
     sphere_instance_buffer: glow::Buffer,
     stick_instance_buffer: glow::Buffer,
+    cone_instance_buffer: glow::Buffer,
     instance_groups: Option<InstanceGroups>,
     u_model: Mat4,
     u_normal_matrix: Mat3,
 }
 
+/// Line segments of one width, lit like surfaces (wireframe edges) or flat colored
+/// (line segments), drawn with a single `GL_LINES` call.
+struct LineBatch {
+    width: f32,
+    lit: bool,
+    /// Range in `line_indices`.
+    start: usize,
+    count: usize,
+}
+
 #[expect(unsafe_code)] // we need unsafe code to use glow
 impl Shader {
     fn new(gl: &glow::Context, scene: &Scene) -> Option<Self> {
@@ -463,11 +539,33 @@ impl Shader {
 
             let indices_stick: Vec<u32> = template_stick.indices.clone();
 
+            // =========================
+            // 4.3 Generate cone mesh template
+            // =========================
+            let template_cone = Cone::get_or_generate_cone_mesh_template(2);
+            let vertex3d_cone: Vec<Vertex3d> = template_cone
+                .vertices
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| Vertex3d {
+                    position: *pos,
+                    normal: template_cone.normals[i],
+                    color: default_color.into(),
+                })
+                .collect();
+
+            let indices_cone: Vec<u32> = template_cone.indices.clone();
+
             // =========================
             // 5. Create buffers
             // =========================
             let vbo = gl.create_buffer().expect("Cannot create vertex buffer");
             let ebo = gl.create_buffer().expect("Cannot create element buffer");
+            let ebo_lines = gl
+                .create_buffer()
+                .expect("Cannot create line element buffer");
+            let mut line_width_range = [1.0f32; 2];
+            gl.get_parameter_f32_slice(glow::ALIASED_LINE_WIDTH_RANGE, &mut line_width_range);
 
             let sphere_vbo = gl
                 .create_buffer()
@@ -513,6 +611,30 @@ impl Shader {
                 glow::STATIC_DRAW,
             );
 
+            let cone_instance_buffer = gl
+                .create_buffer()
+                .expect("Cannot create cone instance buffer");
+
+            let cone_vbo = gl
+                .create_buffer()
+                .expect("Cannot create cone vertex buffer");
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(cone_vbo));
+            gl.buffer_data_u8_slice(
+                glow::ARRAY_BUFFER,
+                bytemuck::cast_slice(&vertex3d_cone),
+                glow::STATIC_DRAW,
+            );
+
+            let cone_ebo = gl
+                .create_buffer()
+                .expect("Cannot create cone element buffer");
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(cone_ebo));
+            gl.buffer_data_u8_slice(
+                glow::ELEMENT_ARRAY_BUFFER,
+                bytemuck::cast_slice(&indices_cone),
+                glow::STATIC_DRAW,
+            );
+
             // =========================
             // 6. Setup VAO for mesh
             // =========================
@@ -619,92 +741,19 @@ impl Shader {
             // =========================
             // 7.2 Setup VAO for instanced sticks
             // =========================
-            let pos_a_position = gl.get_attrib_location(program_stick, "a_position").unwrap();
-            let normal_a_position = gl.get_attrib_location(program_stick, "a_normal").unwrap();
-            let instance_i_start = gl.get_attrib_location(program_stick, "i_start").unwrap();
-            let instance_i_end = gl.get_attrib_location(program_stick, "i_end").unwrap();
-            let instance_i_radius = gl.get_attrib_location(program_stick, "i_radius").unwrap();
-            let instance_i_color = gl.get_attrib_location(program_stick, "i_color").unwrap();
-
-            let vao_stick = gl
-                .create_vertex_array()
-                .expect("Cannot create vertex array");
-            gl.bind_vertex_array(Some(vao_stick));
-
-            // per-vertex attributes
-            gl.bind_buffer(glow::ARRAY_BUFFER, Some(stick_vbo));
-            gl.enable_vertex_attrib_array(pos_a_position); // a_position
-            gl.vertex_attrib_pointer_f32(
-                pos_a_position,
-                3,
-                glow::FLOAT,
-                false,
-                stride_vertex_3d,
-                0,
-            );
-            gl.vertex_attrib_divisor(pos_a_position, 0);
-
-            gl.enable_vertex_attrib_array(normal_a_position); // a_normal
-            gl.vertex_attrib_pointer_f32(
-                normal_a_position,
-                3,
-                glow::FLOAT,
-                false,
-                stride_vertex_3d,
-                3 * 4,
-            );
-            gl.vertex_attrib_divisor(normal_a_position, 0);
-
-            // per-instance attributes
-            gl.bind_buffer(glow::ARRAY_BUFFER, Some(stick_instance_buffer));
-            let stride_instance = std::mem::size_of::<StickInstance>() as i32;
-
-            gl.enable_vertex_attrib_array(instance_i_start); // i_start
-            gl.vertex_attrib_pointer_f32(
-                instance_i_start,
-                3,
-                glow::FLOAT,
-                false,
-                stride_instance,
-                0,
-            );
-            gl.vertex_attrib_divisor(instance_i_start, 1);
-
-            gl.enable_vertex_attrib_array(instance_i_end); // i_end
-            gl.vertex_attrib_pointer_f32(
-                instance_i_end,
-                3,
-                glow::FLOAT,
-                false,
-                stride_instance,
-                3 * 4,
-            );
-            gl.vertex_attrib_divisor(instance_i_end, 1);
-
-            gl.enable_vertex_attrib_array(instance_i_radius); // i_radius
-            gl.vertex_attrib_pointer_f32(
-                instance_i_radius,
-                1,
-                glow::FLOAT,
-                false,
-                stride_instance,
-                6 * 4,
-            );
-            gl.vertex_attrib_divisor(instance_i_radius, 1);
-
-            gl.enable_vertex_attrib_array(instance_i_color); // i_color
-            gl.vertex_attrib_pointer_f32(
-                instance_i_color,
-                4,
-                glow::FLOAT,
-                false,
-                stride_instance,
-                7 * 4,
+            let vao_stick = create_stick_vao(
+                gl,
+                program_stick,
+                stick_vbo,
+                stick_ebo,
+                stick_instance_buffer,
             );
-            gl.vertex_attrib_divisor(instance_i_color, 1);
 
-            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(stick_ebo));
-            gl.bind_vertex_array(None);
+            // =========================
+            // 7.3 Setup VAO for instanced cones (arrow heads), same layout as sticks
+            // =========================
+            let vao_cone =
+                create_stick_vao(gl, program_stick, cone_vbo, cone_ebo, cone_instance_buffer);
 
             gl.use_program(Some(program));
 
@@ -718,17 +767,25 @@ impl Shader {
                 program_stick,
                 vertex3d: vec![],
                 indices: vec![],
+                transparent_index_start: 0,
+                line_indices: vec![],
+                line_batches: vec![],
+                line_width_range,
                 camera_lighting: Lighting::default(),
                 vao_mesh,
                 vao_sphere,
                 vao_stick,
+                vao_cone,
                 sphere_instance_buffer,
                 stick_instance_buffer,
+                cone_instance_buffer,
                 sphere_index_count: indices_sphere.len(),
                 stick_index_count: indices_stick.len(),
+                cone_index_count: indices_cone.len(),
                 background_color,
                 vbo,
                 ebo,
+                ebo_lines,
                 instance_groups: None,
                 u_model: scene.model_matrix(),
                 u_normal_matrix: scene.normal_matrix(),
@@ -753,10 +810,36 @@ impl Shader {
         self.background_color = scene.background_color;
         self.vertex3d.clear();
         self.indices.clear();
+        self.line_indices.clear();
+        self.line_batches.clear();
+        // 按 (线宽, 是否受光) 分批，每批一次 GL_LINES
+        type LineGroups = Vec<((f32, bool), Vec<u32>)>;
+        fn line_group(groups: &mut LineGroups, width: f32, lit: bool) -> &mut Vec<u32> {
+            let i = match groups.iter().position(|(k, _)| *k == (width, lit)) {
+                Some(i) => i,
+                None => {
+                    groups.push(((width, lit), Vec::new()));
+                    groups.len() - 1
+                }
+            };
+            &mut groups[i].1
+        }
+        let mut line_groups = LineGroups::new();
 
         let mut vertex_offset = 0u32;
 
-        for mesh in scene._get_meshes() {
+        // 半透明网格放在最后，单独开启混合绘制
+        let (opaque, transparent): (Vec<_>, Vec<_>) =
+            scene._get_meshes().into_iter().partition(|mesh| {
+                mesh.colors
+                    .as_ref()
+                    .is_none_or(|colors| colors.iter().all(|c| c.w >= 1.0))
+            });
+
+        for (n, mesh) in opaque.iter().chain(&transparent).enumerate() {
+            if n == opaque.len() {
+                self.transparent_index_start = self.indices.len();
+            }
             self.vertex3d
                 .extend(mesh.vertices.iter().enumerate().map(|(i, pos)| {
                     Vertex3d {
@@ -770,10 +853,40 @@ impl Shader {
                     }
                 }));
 
-            self.indices
-                .extend(mesh.indices.iter().map(|&i| i + vertex_offset));
+            let width = mesh.line_width.unwrap_or(1.0);
+            if mesh.is_wireframe {
+                // 相邻三角形共享的边只画一次；线框边保留表面法线，照常受光
+                let group = line_group(&mut line_groups, width, true);
+                let mut edges = std::collections::HashSet::new();
+                for tri in mesh.indices.chunks_exact(3) {
+                    for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                        if edges.insert((a.min(b), a.max(b))) {
+                            group.extend([a + vertex_offset, b + vertex_offset]);
+                        }
+                    }
+                }
+            } else {
+                self.indices
+                    .extend(mesh.indices.iter().map(|&i| i + vertex_offset));
+            }
+            if !mesh.line_indices.is_empty() {
+                line_group(&mut line_groups, width, false)
+                    .extend(mesh.line_indices.iter().map(|&i| i + vertex_offset));
+            }
             vertex_offset += mesh.vertices.len() as u32;
         }
+        if transparent.is_empty() {
+            self.transparent_index_start = self.indices.len();
+        }
+        for ((width, lit), indices) in line_groups {
+            self.line_batches.push(LineBatch {
+                width,
+                lit,
+                start: self.line_indices.len(),
+                count: indices.len(),
+            });
+            self.line_indices.extend(indices);
+        }
 
         self.instance_groups = Some(scene.get_instances_grouped());
 
@@ -782,6 +895,13 @@ impl Shader {
         }
     }
 
+    /// Sphere instances of the current scene, in scene units.
+    fn spheres(&self) -> &[SphereInstance] {
+        self.instance_groups
+            .as_ref()
+            .map_or(&[], |groups| groups.spheres.as_slice())
+    }
+
     fn paint(&mut self, gl: &glow::Context, aspect_ratio: f32, camera_state: &CameraState) {
         let (u_view, u_projection, u_view_pos) = camera_state.matrices(aspect_ratio);
 
@@ -905,11 +1025,34 @@ impl Shader {
 
             gl.draw_elements(
                 glow::TRIANGLES,
-                self.indices.len() as i32,
+                self.transparent_index_start as i32,
                 glow::UNSIGNED_INT,
                 0,
             );
 
+            if !self.line_indices.is_empty() {
+                gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo_lines));
+                gl.buffer_data_u8_slice(
+                    glow::ELEMENT_ARRAY_BUFFER,
+                    bytemuck::cast_slice(&self.line_indices),
+                    glow::DYNAMIC_DRAW,
+                );
+                // 宽线在核心模式下可能不被支持，按驱动范围截断
+                let [min_width, max_width] = self.line_width_range;
+                let u_unlit = gl.get_uniform_location(self.program, "u_unlit");
+                for batch in &self.line_batches {
+                    gl.line_width(batch.width.clamp(min_width, max_width.max(min_width)));
+                    gl.uniform_1_i32(u_unlit.as_ref(), (!batch.lit) as i32);
+                    gl.draw_elements(
+                        glow::LINES,
+                        batch.count as i32,
+                        glow::UNSIGNED_INT,
+                        (batch.start * std::mem::size_of::<u32>()) as i32,
+                    );
+                }
+                gl.uniform_1_i32(u_unlit.as_ref(), 0);
+            }
+
             if let Some(instance_groups) = &self.instance_groups {
                 gl.use_program(Some(self.program_sphere));
                 gl.uniform_matrix_4_f32_slice(
@@ -1037,11 +1180,144 @@ impl Shader {
                     0,
                     instance_groups.sticks.len() as i32,
                 );
+
+                if !instance_groups.cones.is_empty() {
+                    gl.bind_vertex_array(Some(self.vao_cone));
+                    gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.cone_instance_buffer));
+                    gl.buffer_data_u8_slice(
+                        glow::ARRAY_BUFFER,
+                        bytemuck::cast_slice(&instance_groups.cones),
+                        glow::DYNAMIC_DRAW,
+                    );
+
+                    gl.draw_elements_instanced(
+                        glow::TRIANGLES,
+                        self.cone_index_count as i32,
+                        glow::UNSIGNED_INT,
+                        0,
+                        instance_groups.cones.len() as i32,
+                    );
+                }
+            }
+
+            // === 半透明网格：混合，且不写深度 ===
+            let transparent_count = self.indices.len() - self.transparent_index_start;
+            if transparent_count > 0 {
+                gl.enable(glow::BLEND);
+                gl.blend_func_separate(
+                    glow::SRC_ALPHA,
+                    glow::ONE_MINUS_SRC_ALPHA,
+                    glow::ONE,
+                    glow::ONE_MINUS_SRC_ALPHA,
+                );
+                gl.depth_mask(false);
+
+                gl.use_program(Some(self.program));
+                gl.bind_vertex_array(Some(self.vao_mesh));
+                gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
+                gl.draw_elements(
+                    glow::TRIANGLES,
+                    transparent_count as i32,
+                    glow::UNSIGNED_INT,
+                    (self.transparent_index_start * std::mem::size_of::<u32>()) as i32,
+                );
+
+                gl.depth_mask(true);
+                gl.disable(glow::BLEND);
             }
         }
     }
 }
 
+/// VAO drawing a unit template (cylinder or cone along +Z) once per [`StickInstance`].
+#[expect(unsafe_code)]
+unsafe fn create_stick_vao(
+    gl: &glow::Context,
+    program_stick: glow::Program,
+    vbo: glow::Buffer,
+    ebo: glow::Buffer,
+    instance_buffer: glow::Buffer,
+) -> glow::VertexArray {
+    use glow::HasContext as _;
+
+    let stride_vertex_3d = std::mem::size_of::<Vertex3d>() as i32;
+    unsafe {
+        let pos_a_position = gl.get_attrib_location(program_stick, "a_position").unwrap();
+        let normal_a_position = gl.get_attrib_location(program_stick, "a_normal").unwrap();
+        let instance_i_start = gl.get_attrib_location(program_stick, "i_start").unwrap();
+        let instance_i_end = gl.get_attrib_location(program_stick, "i_end").unwrap();
+        let instance_i_radius = gl.get_attrib_location(program_stick, "i_radius").unwrap();
+        let instance_i_color = gl.get_attrib_location(program_stick, "i_color").unwrap();
+
+        let vao = gl
+            .create_vertex_array()
+            .expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(vao));
+
+        // per-vertex attributes
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+        gl.enable_vertex_attrib_array(pos_a_position); // a_position
+        gl.vertex_attrib_pointer_f32(pos_a_position, 3, glow::FLOAT, false, stride_vertex_3d, 0);
+        gl.vertex_attrib_divisor(pos_a_position, 0);
+
+        gl.enable_vertex_attrib_array(normal_a_position); // a_normal
+        gl.vertex_attrib_pointer_f32(
+            normal_a_position,
+            3,
+            glow::FLOAT,
+            false,
+            stride_vertex_3d,
+            3 * 4,
+        );
+        gl.vertex_attrib_divisor(normal_a_position, 0);
+
+        // per-instance attributes
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(instance_buffer));
+        let stride_instance = std::mem::size_of::<StickInstance>() as i32;
+
+        gl.enable_vertex_attrib_array(instance_i_start); // i_start
+        gl.vertex_attrib_pointer_f32(instance_i_start, 3, glow::FLOAT, false, stride_instance, 0);
+        gl.vertex_attrib_divisor(instance_i_start, 1);
+
+        gl.enable_vertex_attrib_array(instance_i_end); // i_end
+        gl.vertex_attrib_pointer_f32(
+            instance_i_end,
+            3,
+            glow::FLOAT,
+            false,
+            stride_instance,
+            3 * 4,
+        );
+        gl.vertex_attrib_divisor(instance_i_end, 1);
+
+        gl.enable_vertex_attrib_array(instance_i_radius); // i_radius
+        gl.vertex_attrib_pointer_f32(
+            instance_i_radius,
+            1,
+            glow::FLOAT,
+            false,
+            stride_instance,
+            6 * 4,
+        );
+        gl.vertex_attrib_divisor(instance_i_radius, 1);
+
+        gl.enable_vertex_attrib_array(instance_i_color); // i_color
+        gl.vertex_attrib_pointer_f32(
+            instance_i_color,
+            4,
+            glow::FLOAT,
+            false,
+            stride_instance,
+            7 * 4,
+        );
+        gl.vertex_attrib_divisor(instance_i_color, 1);
+
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(ebo));
+        gl.bind_vertex_array(None);
+        vao
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct CameraState {
     pub target: Vec3,
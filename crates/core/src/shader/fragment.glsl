@@ -4,6 +4,8 @@ uniform vec3 u_light_pos;
 uniform vec3 u_light_color;
 uniform vec3 u_view_pos;
 uniform float u_light_intensity;
+// Set while drawing line segments, which are flat colored
+uniform bool u_unlit;
 
 in vec3 v_normal;
 in vec3 v_frag_pos;
@@ -12,6 +14,11 @@ in vec4 v_color;
 out vec4 FragColor;
 
 void main() {
+    if (u_unlit) {
+        FragColor = vec4(v_color.rgb * u_light_intensity, v_color.a);
+        return;
+    }
+
     // Normalize once
     vec3 N = normalize(v_normal);
     vec3 L = normalize(u_light_pos - v_frag_pos);
@@ -1,3 +1,3 @@
 mod canvas;
 
-pub use canvas::*;
\ No newline at end of file
+pub use canvas::*;
@@ -18,8 +18,8 @@ void main() {
     vec4 world_pos = u_model * vec4(a_position, 1.0);
     v_frag_pos = world_pos.xyz;
 
-    // 2. 法线也要变换到世界空间
-    v_normal = normalize(u_normal_matrix * a_normal);
+    // 2. 法线也要变换到世界空间；零法线（线段）保持为零，片元着色器不做光照
+    v_normal = dot(a_normal, a_normal) > 0.0 ? normalize(u_normal_matrix * a_normal) : vec3(0.0);
 
     // 3. 把顶点变换到最终裁剪空间用于光栅化
     gl_Position = u_projection * u_view * world_pos;
@@ -40,8 +40,9 @@ void main() {
     vec4 world_pos = u_model * transformed;
     v_frag_pos = world_pos.xyz;
 
-    // 5️⃣ 法线变换到世界空间
-    vec3 normal_world = rot * a_normal;
+    // 5️⃣ 法线变换到世界空间（非均匀缩放时按缩放的逆修正，圆锥侧面需要）
+    vec3 normal_local = normalize(vec3(a_normal.xy / i_radius, a_normal.z / max(len, 1e-6)));
+    vec3 normal_world = rot * normal_local;
     v_normal = normalize(u_normal_matrix * normal_world);
 
     // 6️⃣ 输出颜色
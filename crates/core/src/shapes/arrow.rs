@@ -0,0 +1,155 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Shape,
+    shapes::StickInstance,
+    utils::{
+        InstanceGroups, Interaction, Interpolatable, IntoInstanceGroups, Logger, MeshData,
+        VisualShape, VisualStyle,
+    },
+};
+
+/// Vector drawn as a cylindrical shaft with a cone head at `end`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Arrow {
+    pub start: [f32; 3],
+    pub end: [f32; 3],
+    /// Shaft radius.
+    pub radius: f32,
+    /// Head length as a fraction of the whole arrow length.
+    pub head_ratio: f32,
+    /// Head base radius relative to the shaft radius.
+    pub head_width: f32,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Arrow {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        let lerp = |a: f32, b: f32| a * (1.0 - t) + b * t;
+        Self {
+            start: Vec3::from(self.start)
+                .lerp(Vec3::from(other.start), t)
+                .to_array(),
+            end: Vec3::from(self.end)
+                .lerp(Vec3::from(other.end), t)
+                .to_array(),
+            radius: lerp(self.radius, other.radius),
+            head_ratio: lerp(self.head_ratio, other.head_ratio),
+            head_width: lerp(self.head_width, other.head_width),
+            ..*self
+        }
+    }
+}
+
+impl Into<Shape> for Arrow {
+    fn into(self) -> Shape {
+        Shape::Arrow(self)
+    }
+}
+
+impl VisualShape for Arrow {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Arrow {
+    pub fn new(start: [f32; 3], end: [f32; 3], radius: f32) -> Self {
+        Self {
+            start,
+            end,
+            radius,
+            head_ratio: 0.25,
+            head_width: 2.0,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    /// Arrow from `origin` along `vector`, e.g. a dipole or force.
+    pub fn from_vector(origin: [f32; 3], vector: [f32; 3], radius: f32) -> Self {
+        let end = Vec3::from(origin) + Vec3::from(vector);
+        Self::new(origin, end.to_array(), radius)
+    }
+
+    pub fn set_start(mut self, start: [f32; 3]) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn set_end(mut self, end: [f32; 3]) -> Self {
+        self.end = end;
+        self
+    }
+
+    pub fn set_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Head length as a fraction (0..1) of the arrow length.
+    pub fn head_ratio(mut self, ratio: f32) -> Self {
+        self.head_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Head base radius as a multiple of the shaft radius.
+    pub fn head_width(mut self, width: f32) -> Self {
+        self.head_width = width;
+        self
+    }
+
+    /// Arrows are drawn as instances, see [`Arrow::to_instance_group`].
+    pub fn to_mesh(&self, _scale: f32) -> MeshData {
+        MeshData::default()
+    }
+
+    /// Shaft stick, head cone and a small cone closing the tail.
+    ///
+    /// Cones use the stick layout: base at `start`, tip at `end`, drawn with
+    /// [`Cone::get_or_generate_cone_mesh_template`](crate::shapes::Cone::get_or_generate_cone_mesh_template).
+    pub fn to_instances(&self, scale: f32) -> (StickInstance, [StickInstance; 2]) {
+        let base_color = self.style.color.unwrap_or([1.0, 1.0, 1.0].into());
+        let alpha = self.style.opacity.clamp(0.0, 1.0);
+        let color = [base_color[0], base_color[1], base_color[2], alpha];
+
+        let start = Vec3::from(self.start) * scale;
+        let end = Vec3::from(self.end) * scale;
+        let dir = (end - start).normalize_or_zero();
+        let radius = self.radius * scale;
+        let head_start = end - (end - start) * self.head_ratio.clamp(0.0, 1.0);
+        // 尾部用极短的圆锥封口，底面朝后
+        let tail_tip = start + dir * radius * 0.01;
+
+        (
+            StickInstance::new(start.to_array(), head_start.to_array(), radius, color),
+            [
+                StickInstance::new(
+                    head_start.to_array(),
+                    end.to_array(),
+                    radius * self.head_width,
+                    color,
+                ),
+                StickInstance::new(start.to_array(), tail_tip.to_array(), radius, color),
+            ],
+        )
+    }
+}
+
+impl IntoInstanceGroups for Arrow {
+    fn to_instance_group(&self, scale: f32) -> InstanceGroups {
+        let (shaft, cones) = self.to_instances(scale);
+        InstanceGroups {
+            sticks: vec![shaft],
+            cones: cones.to_vec(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::Arc;
+use dashmap::DashMap;
+use glam::{Mat4, Quat, Vec3};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Shape,
+    shapes::sphere::MeshTemplate,
+    utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle},
+};
+
+static CONE_TEMPLATE_CACHE: Lazy<DashMap<u32, Arc<MeshTemplate>>> = Lazy::new(DashMap::new);
+
+/// Solid cone from the center of its base disk to its tip.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Cone {
+    pub base: [f32; 3],
+    pub tip: [f32; 3],
+    /// Radius of the base disk.
+    pub radius: f32,
+    pub quality: u32,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Cone {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        Self {
+            base: Vec3::from(self.base)
+                .lerp(Vec3::from(other.base), t)
+                .to_array(),
+            tip: Vec3::from(self.tip)
+                .lerp(Vec3::from(other.tip), t)
+                .to_array(),
+            radius: self.radius * (1.0 - t) + other.radius * t,
+            ..*self
+        }
+    }
+}
+
+impl Into<Shape> for Cone {
+    fn into(self) -> Shape {
+        Shape::Cone(self)
+    }
+}
+
+impl VisualShape for Cone {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Cone {
+    pub fn new(base: [f32; 3], tip: [f32; 3], radius: f32) -> Self {
+        Self {
+            base,
+            tip,
+            radius,
+            quality: 2,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    pub fn set_base(mut self, base: [f32; 3]) -> Self {
+        self.base = base;
+        self
+    }
+
+    pub fn set_tip(mut self, tip: [f32; 3]) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn set_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let base = Vec3::from(self.base);
+        let axis = Vec3::from(self.tip) - base;
+        let rotation = Quat::from_rotation_arc(Vec3::Z, axis.normalize_or(Vec3::Z));
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::new(self.radius, self.radius, axis.length().max(1e-6)) * scale,
+            rotation,
+            base * scale,
+        );
+        let color = self.style.color.unwrap_or(Vec3::ONE);
+        Self::get_or_generate_cone_mesh_template(self.quality).to_mesh(
+            transform,
+            color.extend(self.style.opacity.clamp(0.0, 1.0)),
+            self.style.wireframe,
+        )
+    }
+
+    /// Unit cone along +Z with base radius 1 at z = 0, tip at z = 1, closed by a base disk.
+    pub fn get_or_generate_cone_mesh_template(quality: u32) -> Arc<MeshTemplate> {
+        if let Some(entry) = CONE_TEMPLATE_CACHE.get(&quality) {
+            return Arc::clone(entry.value());
+        }
+
+        let segments = 10 * quality.max(1);
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut indices = Vec::new();
+
+        // 侧面：每段单独的顶点，使尖端法线随方向变化
+        for i in 0..segments {
+            let base = vertices.len() as u32;
+            let angle = |k: u32| k as f32 / segments as f32 * std::f32::consts::TAU;
+            let (a0, a1) = (angle(i), angle(i + 1));
+            let mid = (a0 + a1) * 0.5;
+            // 半径 1、高 1 的圆锥侧面法线为 (cos, sin, 1) / √2
+            let normal = |a: f32| Vec3::new(a.cos(), a.sin(), 1.0).normalize();
+
+            vertices.extend([
+                Vec3::new(a0.cos(), a0.sin(), 0.0),
+                Vec3::new(a1.cos(), a1.sin(), 0.0),
+                Vec3::Z,
+            ]);
+            normals.extend([normal(a0), normal(a1), normal(mid)]);
+            indices.extend([base, base + 1, base + 2]);
+        }
+
+        let center = vertices.len() as u32;
+        vertices.push(Vec3::ZERO);
+        normals.push(Vec3::NEG_Z);
+        for i in 0..=segments {
+            let a = i as f32 / segments as f32 * std::f32::consts::TAU;
+            vertices.push(Vec3::new(a.cos(), a.sin(), 0.0));
+            normals.push(Vec3::NEG_Z);
+        }
+        for i in 0..segments {
+            indices.extend([center, center + i + 2, center + i + 1]);
+        }
+
+        let template = Arc::new(MeshTemplate {
+            vertices,
+            normals,
+            indices,
+        });
+        CONE_TEMPLATE_CACHE.insert(quality, Arc::clone(&template));
+        template
+    }
+}
@@ -0,0 +1,138 @@
+use crate::Arc;
+use glam::{Mat4, Quat, Vec3};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Shape,
+    shapes::sphere::MeshTemplate,
+    utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle},
+};
+
+/// Unit cube from -0.5 to 0.5, with separate vertices per face for flat shading.
+static BOX_TEMPLATE: Lazy<Arc<MeshTemplate>> = Lazy::new(|| {
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    for normal in [
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Y,
+        Vec3::NEG_Y,
+        Vec3::Z,
+        Vec3::NEG_Z,
+    ] {
+        // u × v = normal，保证逆时针为正面
+        let u = normal.any_orthonormal_vector();
+        let v = normal.cross(u);
+        let base = vertices.len() as u32;
+        for (su, sv) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            vertices.push((normal + u * su + v * sv) * 0.5);
+            normals.push(normal);
+        }
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    Arc::new(MeshTemplate {
+        vertices,
+        normals,
+        indices,
+    })
+});
+
+/// Rectangular box, e.g. a bounding box or a docking search space.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Cuboid {
+    pub center: [f32; 3],
+    /// Edge lengths along the box's local x, y and z.
+    pub size: [f32; 3],
+    pub rotation: Quat,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Cuboid {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        Self {
+            center: Vec3::from(self.center)
+                .lerp(Vec3::from(other.center), t)
+                .to_array(),
+            size: Vec3::from(self.size)
+                .lerp(Vec3::from(other.size), t)
+                .to_array(),
+            rotation: self.rotation.slerp(other.rotation, t),
+            ..*self
+        }
+    }
+}
+
+impl Into<Shape> for Cuboid {
+    fn into(self) -> Shape {
+        Shape::Cuboid(self)
+    }
+}
+
+impl VisualShape for Cuboid {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Cuboid {
+    pub fn new(center: [f32; 3], size: [f32; 3]) -> Self {
+        Self {
+            center,
+            size,
+            rotation: Quat::IDENTITY,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    /// Axis-aligned box between two opposite corners.
+    pub fn from_corners(min: [f32; 3], max: [f32; 3]) -> Self {
+        let (min, max) = (Vec3::from(min), Vec3::from(max));
+        Self::new(((min + max) * 0.5).to_array(), (max - min).abs().to_array())
+    }
+
+    pub fn set_center(mut self, center: [f32; 3]) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn set_size(mut self, size: [f32; 3]) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Rotates the box about its center by `degrees` around `axis`.
+    pub fn rotate(mut self, axis: [f32; 3], degrees: f32) -> Self {
+        let axis = Vec3::from(axis).normalize_or(Vec3::Z);
+        self.rotation = Quat::from_axis_angle(axis, degrees.to_radians()) * self.rotation;
+        self
+    }
+
+    pub fn get_or_generate_box_mesh_template() -> Arc<MeshTemplate> {
+        Arc::clone(&BOX_TEMPLATE)
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::from(self.size) * scale,
+            self.rotation,
+            Vec3::from(self.center) * scale,
+        );
+        let color = self.style.color.unwrap_or(Vec3::ONE);
+        Self::get_or_generate_box_mesh_template().to_mesh(
+            transform,
+            color.extend(self.style.opacity.clamp(0.0, 1.0)),
+            self.style.wireframe,
+        )
+    }
+}
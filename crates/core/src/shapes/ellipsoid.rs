@@ -0,0 +1,210 @@
+use glam::{Mat3, Mat4, Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Shape,
+    shapes::Sphere,
+    utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle},
+};
+
+/// Sphere stretched along three perpendicular axes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Ellipsoid {
+    pub center: [f32; 3],
+    /// Semi-axis lengths along the ellipsoid's local x, y and z.
+    pub radii: [f32; 3],
+    pub rotation: Quat,
+    pub quality: u32,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Ellipsoid {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        Self {
+            center: Vec3::from(self.center)
+                .lerp(Vec3::from(other.center), t)
+                .to_array(),
+            radii: Vec3::from(self.radii)
+                .lerp(Vec3::from(other.radii), t)
+                .to_array(),
+            rotation: self.rotation.slerp(other.rotation, t),
+            ..*self
+        }
+    }
+}
+
+impl Into<Shape> for Ellipsoid {
+    fn into(self) -> Shape {
+        Shape::Ellipsoid(self)
+    }
+}
+
+impl VisualShape for Ellipsoid {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Ellipsoid {
+    pub fn new(center: [f32; 3], radii: [f32; 3]) -> Self {
+        Self {
+            center,
+            radii,
+            rotation: Quat::IDENTITY,
+            quality: 3,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    /// Ellipsoid from its three semi-axis vectors, which should be perpendicular.
+    pub fn from_axes(center: [f32; 3], axes: [[f32; 3]; 3]) -> Self {
+        let axes = axes.map(Vec3::from);
+        let radii = axes.map(|a| a.length());
+        let [x, y, _] = axes.map(|a| a.normalize_or_zero());
+        // 由前两个轴构造右手正交基，避免输入轴略微不正交或为左手系
+        let z = x.cross(y).normalize_or_zero();
+        let y = z.cross(x);
+        let rotation = Quat::from_mat3(&Mat3::from_cols(x, y, z));
+        Self {
+            rotation: if rotation.is_finite() {
+                rotation
+            } else {
+                Quat::IDENTITY
+            },
+            ..Self::new(center, radii)
+        }
+    }
+
+    /// Thermal ellipsoid of an anisotropic displacement tensor.
+    ///
+    /// `u` is the Cartesian tensor in Å² as U11, U22, U33, U12, U13, U23, and the
+    /// ellipsoid encloses the atom with the given `probability` (0.5 in most ORTEP plots).
+    pub fn from_displacement(center: [f32; 3], u: [f32; 6], probability: f32) -> Self {
+        let [u11, u22, u33, u12, u13, u23] = u.map(f64::from);
+        let (values, vectors) =
+            symmetric_eigen([[u11, u12, u13], [u12, u22, u23], [u13, u23, u33]]);
+        let scale = probability_scale(probability);
+        // 非正定张量的负本征值截断成很小的正数
+        let axes = [0, 1, 2].map(|i| {
+            let axis = Vec3::new(
+                vectors[0][i] as f32,
+                vectors[1][i] as f32,
+                vectors[2][i] as f32,
+            );
+            axis * scale * (values[i].max(1e-4) as f32).sqrt()
+        });
+        Self::from_axes(center, axes.map(|a| a.to_array()))
+    }
+
+    /// The three semi-axis vectors.
+    pub fn principal_axes(&self) -> [Vec3; 3] {
+        let [x, y, z] = self.radii;
+        [Vec3::X * x, Vec3::Y * y, Vec3::Z * z].map(|a| self.rotation * a)
+    }
+
+    pub fn set_center(mut self, center: [f32; 3]) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn set_radii(mut self, radii: [f32; 3]) -> Self {
+        self.radii = radii;
+        self
+    }
+
+    /// Rotates the ellipsoid about its center by `degrees` around `axis`.
+    pub fn rotate(mut self, axis: [f32; 3], degrees: f32) -> Self {
+        let axis = Vec3::from(axis).normalize_or(Vec3::Z);
+        self.rotation = Quat::from_axis_angle(axis, degrees.to_radians()) * self.rotation;
+        self
+    }
+
+    /// Uses the unit sphere template of [`Sphere`].
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::from(self.radii).max(Vec3::splat(1e-6)) * scale,
+            self.rotation,
+            Vec3::from(self.center) * scale,
+        );
+        let color = self.style.color.unwrap_or(Vec3::ONE);
+        Sphere::get_or_generate_icosphere_mesh_template(self.quality).to_mesh(
+            transform,
+            color.extend(self.style.opacity.clamp(0.0, 1.0)),
+            self.style.wireframe,
+        )
+    }
+}
+
+/// Radius, in standard deviations, of the sphere holding `probability` of a
+/// 3D normal distribution, e.g. 1.538 for 50 %.
+fn probability_scale(probability: f32) -> f32 {
+    let p = probability.clamp(0.01, 0.999) as f64;
+    // 三维正态分布的径向累积分布：erf(c/√2) - √(2/π)·c·exp(-c²/2)，二分求解
+    let cdf = |c: f64| {
+        erf(c / std::f64::consts::SQRT_2)
+            - (2.0 / std::f64::consts::PI).sqrt() * c * (-c * c / 2.0).exp()
+    };
+    let (mut lo, mut hi) = (0.0, 10.0);
+    for _ in 0..60 {
+        let mid = (lo + hi) / 2.0;
+        if cdf(mid) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    ((lo + hi) / 2.0) as f32
+}
+
+/// Abramowitz & Stegun 7.1.26, accurate to about 1e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    (1.0 - poly * (-x * x).exp()).copysign(x)
+}
+
+/// Eigenvalues and eigenvectors (columns) of a symmetric 3x3 matrix, by Jacobi rotations.
+fn symmetric_eigen(mut a: [[f64; 3]; 3]) -> ([f64; 3], [[f64; 3]; 3]) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..50 {
+        let off = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+        if off < 1e-20 {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() < 1e-30 {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            for row in a.iter_mut() {
+                let (kp, kq) = (row[p], row[q]);
+                row[p] = c * kp - s * kq;
+                row[q] = s * kp + c * kq;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            for k in 0..3 {
+                a[p][k] = c * row_p[k] - s * row_q[k];
+                a[q][k] = s * row_p[k] + c * row_q[k];
+            }
+            for row in v.iter_mut() {
+                let (kp, kq) = (row[p], row[q]);
+                row[p] = c * kp - s * kq;
+                row[q] = s * kp + c * kq;
+            }
+        }
+    }
+    ([a[0][0], a[1][1], a[2][2]], v)
+}
@@ -0,0 +1,199 @@
+use crate::Shape;
+use crate::parser::ccp4::Ccp4Map;
+use crate::shapes::{Molecule, Protein, VolumeGrid};
+use crate::utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle};
+use glam::Vec3;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+/// Contour surface of a volumetric map, drawn as a solid mesh or as a wireframe.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Isosurface {
+    pub grid: VolumeGrid,
+    /// Mean and RMS deviation of the full map, kept so that sigma levels do not
+    /// change when the grid is cropped.
+    pub mean: f32,
+    pub rms: f32,
+    /// Absolute contour level.
+    pub level: f32,
+    /// The grid covers a whole unit cell and may be repeated when cropping.
+    pub periodic: bool,
+    /// When set, also contours `-level` in this color, e.g. the two phases of an orbital.
+    pub negative_color: Option<Vec3>,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+
+    #[serde(skip)]
+    mesh_cache: OnceCell<MeshData>,
+}
+
+impl Interpolatable for Isosurface {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        let mut grid = self.grid.clone();
+        if self.grid.dims == other.grid.dims {
+            grid.origin = self.grid.origin.lerp(other.grid.origin, t);
+            for (v, o) in grid.values.iter_mut().zip(&other.grid.values) {
+                *v = *v * (1.0 - t) + o * t;
+            }
+        }
+
+        Self {
+            grid,
+            level: self.level * (1.0 - t) + other.level * t,
+            mesh_cache: OnceCell::new(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Into<Shape> for Isosurface {
+    fn into(self) -> Shape {
+        Shape::Isosurface(self)
+    }
+}
+
+impl VisualShape for Isosurface {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        self.mesh_cache = OnceCell::new();
+        &mut self.style
+    }
+}
+
+impl Isosurface {
+    /// Contours `grid` at 1σ above its mean.
+    pub fn new(grid: VolumeGrid) -> Self {
+        let (mean, rms) = grid.mean_and_sigma();
+        Self {
+            grid,
+            mean,
+            rms,
+            level: mean + rms,
+            periodic: false,
+            negative_color: None,
+            style: VisualStyle {
+                color: Some(Vec3::new(0.3, 0.5, 1.0)),
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+            mesh_cache: OnceCell::new(),
+        }
+    }
+
+    pub fn from_map(map: &Ccp4Map) -> Self {
+        Self {
+            mean: map.mean,
+            rms: map.rms,
+            level: map.sigma_level(1.0),
+            periodic: map.is_periodic(),
+            ..Self::new(map.grid.clone())
+        }
+    }
+
+    /// Positive (blue) and negative (red) lobes of an orbital at `±iso`.
+    pub fn orbital(grid: VolumeGrid, iso: f32) -> Self {
+        Self::new(grid)
+            .level(iso.abs())
+            .color([0.2, 0.4, 1.0])
+            .dual_lobe([1.0, 0.3, 0.2])
+    }
+
+    /// Also draws the `-level` contour in `negative_color`.
+    pub fn dual_lobe(mut self, negative_color: [f32; 3]) -> Self {
+        self.negative_color = Some(negative_color.into());
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    /// Reads a CCP4 / MRC map file.
+    pub fn from_ccp4(bytes: &[u8]) -> std::io::Result<Self> {
+        Ok(Self::from_map(&Ccp4Map::new(bytes)?))
+    }
+
+    /// Sets the contour level as an absolute map value.
+    pub fn level(mut self, level: f32) -> Self {
+        self.level = level;
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    /// Sets the contour level in units of the map RMS above the mean.
+    pub fn sigma(self, sigma: f32) -> Self {
+        let level = self.mean + sigma * self.rms;
+        self.level(level)
+    }
+
+    /// Current contour level in sigma units.
+    pub fn get_sigma(&self) -> f32 {
+        (self.level - self.mean) / self.rms.max(f32::EPSILON)
+    }
+
+    /// Keeps only the part of the map within `padding` Å of `points`.
+    pub fn crop_to_points(mut self, points: &[Vec3], padding: f32) -> Self {
+        self.grid = self.grid.crop_to_points(points, padding, self.periodic);
+        self.periodic = false;
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    pub fn crop_around_molecule(self, molecule: &Molecule, padding: f32) -> Self {
+        self.crop_to_points(&molecule.atom_posits, padding)
+    }
+
+    pub fn crop_around_protein(self, protein: &Protein, padding: f32) -> Self {
+        let points: Vec<Vec3> = protein
+            .chains
+            .iter()
+            .flat_map(|c| &c.residues)
+            .flat_map(|r| r.real_backbone())
+            .map(|(p, _)| p)
+            .collect();
+        self.crop_to_points(&points, padding)
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let mesh = self.mesh_cache.get_or_init(|| {
+            let alpha = self.style.opacity.clamp(0.0, 1.0);
+            let color = self.style.color.unwrap_or(Vec3::ONE).extend(alpha);
+            let negated = || {
+                let mut grid = self.grid.clone();
+                grid.values.iter_mut().for_each(|v| *v = -*v);
+                grid
+            };
+
+            let mut mesh = match self.negative_color {
+                Some(negative_color) => {
+                    let level = self.level.abs();
+                    let mut mesh = self.grid.marching_cubes(level);
+                    mesh.colors = Some(vec![color; mesh.vertices.len()]);
+                    let mut negative = negated().marching_cubes(level);
+                    negative.colors =
+                        Some(vec![negative_color.extend(alpha); negative.vertices.len()]);
+                    mesh.append(&negative);
+                    mesh
+                }
+                // 低于均值的等值面（如差值图的负密度）包住的是更小的值，取反后法线才朝外
+                None if self.level < self.mean => {
+                    let mut mesh = negated().marching_cubes(-self.level);
+                    mesh.colors = Some(vec![color; mesh.vertices.len()]);
+                    mesh
+                }
+                None => {
+                    let mut mesh = self.grid.marching_cubes(self.level);
+                    mesh.colors = Some(vec![color; mesh.vertices.len()]);
+                    mesh
+                }
+            };
+            mesh.is_wireframe = self.style.wireframe;
+            mesh
+        });
+
+        MeshData {
+            vertices: mesh.vertices.iter().map(|v| *v * scale).collect(),
+            line_width: self.style.line_width,
+            ..mesh.clone()
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::Shape;
+use crate::shapes::{Molecule, Protein};
+use crate::utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle};
+use glam::Vec3;
+use na_seq::AaIdent;
+use serde::{Deserialize, Serialize};
+
+/// How the text height of a label is measured.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum LabelSize {
+    /// Constant height in screen points, whatever the zoom.
+    Screen(f32),
+    /// Height in Å, so labels shrink with distance like the geometry.
+    World(f32),
+}
+
+/// Text anchored to world positions, drawn facing the camera on top of the scene.
+///
+/// One shape holds any number of labels sharing the same look, which keeps
+/// labelling every atom of a large structure cheap.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Labels {
+    pub texts: Vec<String>,
+    pub positions: Vec<Vec3>,
+    pub size: LabelSize,
+    /// Screen offset in points from the anchor, +y pointing down.
+    pub offset: [f32; 2],
+    /// Hide labels whose anchor is behind a sphere instance (atoms in sphere or
+    /// ball-and-stick styles). Sticks, cones and meshes are not tested.
+    pub occlusion: bool,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Labels {
+    fn interpolate(&self, other: &Self, t: f32, logger: impl Logger) -> Self {
+        if self.positions.len() != other.positions.len() {
+            logger.warn(format!(
+                "Labels interpolation skipped: label count differs (self: {}, other: {})",
+                self.positions.len(),
+                other.positions.len()
+            ));
+            return self.clone();
+        }
+
+        Self {
+            positions: self
+                .positions
+                .iter()
+                .zip(&other.positions)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Into<Shape> for Labels {
+    fn into(self) -> Shape {
+        Shape::Labels(self)
+    }
+}
+
+impl VisualShape for Labels {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Self {
+            texts: Vec::new(),
+            positions: Vec::new(),
+            size: LabelSize::Screen(14.0),
+            offset: [0.0, 0.0],
+            occlusion: true,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    /// A single label.
+    pub fn single(text: impl Into<String>, position: [f32; 3]) -> Self {
+        Self::new().add(text, position)
+    }
+
+    pub fn add(mut self, text: impl Into<String>, position: [f32; 3]) -> Self {
+        self.texts.push(text.into());
+        self.positions.push(position.into());
+        self
+    }
+
+    /// Labels every atom with its element symbol.
+    pub fn atom_elements(molecule: &Molecule) -> Self {
+        Self {
+            texts: molecule.atom_types.iter().map(|e| e.to_letter()).collect(),
+            positions: molecule.atom_posits.clone(),
+            ..Self::new()
+        }
+    }
+
+    /// Labels every atom with its index, optionally prefixed by the element, e.g. `C12`.
+    pub fn atom_indices(molecule: &Molecule, with_element: bool) -> Self {
+        Self {
+            texts: molecule
+                .atom_types
+                .iter()
+                .enumerate()
+                .map(|(i, e)| match with_element {
+                    true => format!("{}{}", e.to_letter(), i),
+                    false => i.to_string(),
+                })
+                .collect(),
+            positions: molecule.atom_posits.clone(),
+            ..Self::new()
+        }
+    }
+
+    /// Labels every residue at its CA with name and number, e.g. `ALA 42`.
+    pub fn residues(protein: &Protein) -> Self {
+        let residues = protein.chains.iter().flat_map(|c| &c.residues);
+        Self {
+            texts: residues
+                .clone()
+                .map(|r| format!("{} {}", r.residue_type.to_str(AaIdent::ThreeLetters), r.sns))
+                .collect(),
+            positions: residues.map(|r| r.ca).collect(),
+            ..Self::new()
+        }
+    }
+
+    /// Labels every chain with its ID at the centroid of its CA atoms.
+    pub fn chains(protein: &Protein) -> Self {
+        let chains = protein.chains.iter().filter(|c| !c.residues.is_empty());
+        Self {
+            texts: chains.clone().map(|c| c.id.clone()).collect(),
+            positions: chains
+                .map(|c| c.residues.iter().map(|r| r.ca).sum::<Vec3>() / c.residues.len() as f32)
+                .collect(),
+            size: LabelSize::Screen(20.0),
+            ..Self::new()
+        }
+    }
+
+    /// Text height in screen points.
+    pub fn screen_size(mut self, size: f32) -> Self {
+        self.size = LabelSize::Screen(size);
+        self
+    }
+
+    /// Text height in Å; labels then scale with zoom like the rest of the scene.
+    pub fn world_size(mut self, size: f32) -> Self {
+        self.size = LabelSize::World(size);
+        self
+    }
+
+    pub fn offset(mut self, offset: [f32; 2]) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Whether labels behind atoms are hidden (default) or always drawn on top.
+    ///
+    /// Only sphere instances occlude; labels behind sticks, cones or meshes stay visible.
+    pub fn occlusion(mut self, occlusion: bool) -> Self {
+        self.occlusion = occlusion;
+        self
+    }
+
+    /// Labels are painted by the UI overlay, not as scene geometry.
+    pub fn to_mesh(&self, _scale: f32) -> MeshData {
+        MeshData::default()
+    }
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
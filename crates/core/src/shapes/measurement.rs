@@ -0,0 +1,291 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::Shape;
+use crate::parser::utils::dihedral_angle;
+use crate::shapes::{Labels, Molecule, Stick};
+use crate::utils::{
+    InstanceGroups, Interaction, Interpolatable, IntoInstanceGroups, Logger, MeshData, VisualShape,
+    VisualStyle,
+};
+
+const ARC_SEGMENTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeasurementKind {
+    /// Dashed line between two points, labelled in Å.
+    Distance,
+    /// Arc at the middle of three points, labelled in degrees.
+    Angle,
+    /// Arc around the central bond of four points, labelled in degrees.
+    Dihedral,
+}
+
+#[derive(Error, Debug)]
+pub enum MeasurementError {
+    #[error("Atom index {0} out of range for a molecule with {1} atoms")]
+    AtomOutOfRange(usize, usize),
+}
+
+/// Distance, angle or dihedral annotation between atoms or points.
+///
+/// When created from a molecule the atom indices are kept; with
+/// [`Measurement::follow`] the positions are re-read from the named molecule of
+/// every rendered scene, so the annotation moves with the atoms in animations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Measurement {
+    pub kind: MeasurementKind,
+    /// Atom indices into the molecule, empty for plain points.
+    pub atoms: Vec<usize>,
+    pub positions: Vec<Vec3>,
+    /// ID of the scene molecule the atoms are read from.
+    pub molecule_id: Option<String>,
+    pub line_radius: f32,
+    /// Dash and gap length in Å.
+    pub dash: [f32; 2],
+    pub label_size: f32,
+    pub show_label: bool,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Measurement {
+    fn interpolate(&self, other: &Self, t: f32, logger: impl Logger) -> Self {
+        if self.positions.len() != other.positions.len() {
+            logger.warn("Measurement interpolation skipped: kinds differ");
+            return self.clone();
+        }
+
+        Self {
+            positions: self
+                .positions
+                .iter()
+                .zip(&other.positions)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Into<Shape> for Measurement {
+    fn into(self) -> Shape {
+        Shape::Measurement(self)
+    }
+}
+
+impl VisualShape for Measurement {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Measurement {
+    fn new(kind: MeasurementKind, positions: Vec<Vec3>) -> Self {
+        Self {
+            kind,
+            atoms: Vec::new(),
+            positions,
+            molecule_id: None,
+            line_radius: 0.04,
+            dash: [0.25, 0.2],
+            label_size: 14.0,
+            show_label: true,
+            style: VisualStyle {
+                color: Some(Vec3::new(1.0, 0.85, 0.1)),
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    fn from_atoms(
+        kind: MeasurementKind,
+        molecule: &Molecule,
+        atoms: &[usize],
+    ) -> Result<Self, MeasurementError> {
+        let n = molecule.atom_posits.len();
+        let positions = atoms
+            .iter()
+            .map(|&i| {
+                molecule
+                    .atom_posits
+                    .get(i)
+                    .copied()
+                    .ok_or(MeasurementError::AtomOutOfRange(i, n))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            atoms: atoms.to_vec(),
+            ..Self::new(kind, positions)
+        })
+    }
+
+    pub fn distance(molecule: &Molecule, atoms: [usize; 2]) -> Result<Self, MeasurementError> {
+        Self::from_atoms(MeasurementKind::Distance, molecule, &atoms)
+    }
+
+    /// Angle a-b-c, measured at `atoms[1]`.
+    pub fn angle(molecule: &Molecule, atoms: [usize; 3]) -> Result<Self, MeasurementError> {
+        Self::from_atoms(MeasurementKind::Angle, molecule, &atoms)
+    }
+
+    /// Dihedral a-b-c-d around the b-c bond.
+    pub fn dihedral(molecule: &Molecule, atoms: [usize; 4]) -> Result<Self, MeasurementError> {
+        Self::from_atoms(MeasurementKind::Dihedral, molecule, &atoms)
+    }
+
+    pub fn distance_points(a: [f32; 3], b: [f32; 3]) -> Self {
+        Self::new(MeasurementKind::Distance, vec![a.into(), b.into()])
+    }
+
+    pub fn angle_points(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> Self {
+        Self::new(MeasurementKind::Angle, vec![a.into(), b.into(), c.into()])
+    }
+
+    pub fn dihedral_points(a: [f32; 3], b: [f32; 3], c: [f32; 3], d: [f32; 3]) -> Self {
+        Self::new(
+            MeasurementKind::Dihedral,
+            vec![a.into(), b.into(), c.into(), d.into()],
+        )
+    }
+
+    /// Re-reads the atom positions from the scene molecule added with this ID
+    /// whenever a scene or animation frame is drawn.
+    pub fn follow(mut self, molecule_id: impl Into<String>) -> Self {
+        self.molecule_id = Some(molecule_id.into());
+        self
+    }
+
+    /// Updates the positions from `molecule`; indices out of range keep their old position.
+    pub fn update(mut self, molecule: &Molecule) -> Self {
+        for (p, &i) in self.positions.iter_mut().zip(&self.atoms) {
+            if let Some(posit) = molecule.atom_posits.get(i) {
+                *p = *posit;
+            }
+        }
+        self
+    }
+
+    pub fn line_radius(mut self, radius: f32) -> Self {
+        self.line_radius = radius;
+        self
+    }
+
+    pub fn dash(mut self, dash: f32, gap: f32) -> Self {
+        self.dash = [dash, gap];
+        self
+    }
+
+    pub fn label_size(mut self, size: f32) -> Self {
+        self.label_size = size;
+        self
+    }
+
+    pub fn show_label(mut self, show: bool) -> Self {
+        self.show_label = show;
+        self
+    }
+
+    /// Distance in Å, or angle in degrees (dihedrals in (-180, 180]).
+    pub fn value(&self) -> f32 {
+        let p = &self.positions;
+        match self.kind {
+            MeasurementKind::Distance => p[0].distance(p[1]),
+            MeasurementKind::Angle => (p[0] - p[1]).angle_between(p[2] - p[1]).to_degrees(),
+            MeasurementKind::Dihedral => dihedral_angle(p[0], p[1], p[2], p[3]),
+        }
+    }
+
+    /// Arc points and the label anchor.
+    fn arc(&self) -> (Vec<Vec3>, Vec3) {
+        let p = &self.positions;
+        let (center, u, v, radius, sweep) = match self.kind {
+            MeasurementKind::Distance => return (Vec::new(), (p[0] + p[1]) * 0.5),
+            MeasurementKind::Angle => {
+                let (a, c) = (p[0] - p[1], p[2] - p[1]);
+                let radius = 0.35 * a.length().min(c.length());
+                let u = a.normalize_or_zero();
+                let v = (c - u * c.dot(u))
+                    .try_normalize()
+                    .unwrap_or(u.any_orthonormal_vector());
+                (p[1], u, v, radius, self.value().to_radians())
+            }
+            MeasurementKind::Dihedral => {
+                // 圆弧位于中心键中点、垂直于中心键的平面内，按二面角符号旋转
+                let axis = (p[2] - p[1]).normalize_or_zero();
+                let a = p[0] - p[1];
+                let u = (a - axis * a.dot(axis))
+                    .try_normalize()
+                    .unwrap_or(axis.any_orthonormal_vector());
+                let radius = 0.5 * p[1].distance(p[2]);
+                (
+                    (p[1] + p[2]) * 0.5,
+                    u,
+                    axis.cross(u),
+                    radius,
+                    self.value().to_radians(),
+                )
+            }
+        };
+
+        let at = |s: f32, r: f32| center + (u * (s * sweep).cos() + v * (s * sweep).sin()) * r;
+        let points = (0..=ARC_SEGMENTS)
+            .map(|k| at(k as f32 / ARC_SEGMENTS as f32, radius))
+            .collect();
+        (points, at(0.5, radius * 1.4))
+    }
+
+    /// The value as text, drawn by the label overlay.
+    pub fn to_labels(&self) -> Labels {
+        let (_, anchor) = self.arc();
+        let text = match self.kind {
+            MeasurementKind::Distance => format!("{:.2} Å", self.value()),
+            _ => format!("{:.1}°", self.value()),
+        };
+        let mut labels = Labels::single(text, anchor.to_array())
+            .screen_size(self.label_size)
+            .occlusion(false);
+        labels.style.visible = self.show_label && self.style.visible;
+        labels
+    }
+
+    pub fn to_mesh(&self, _scale: f32) -> MeshData {
+        MeshData::default()
+    }
+}
+
+impl IntoInstanceGroups for Measurement {
+    fn to_instance_group(&self, scale: f32) -> InstanceGroups {
+        let mut groups = InstanceGroups::default();
+        if !self.style.visible {
+            return groups;
+        }
+
+        let stick = |a: Vec3, b: Vec3| {
+            let mut stick = Stick::new(a.to_array(), b.to_array(), self.line_radius);
+            stick.style = self.style;
+            stick
+        };
+        for pair in self.positions.windows(2) {
+            groups
+                .sticks
+                .extend(stick(pair[0], pair[1]).to_dashed_instances(
+                    scale,
+                    self.dash[0],
+                    self.dash[1],
+                ));
+        }
+        let (arc, _) = self.arc();
+        for pair in arc.windows(2) {
+            groups
+                .sticks
+                .push(stick(pair[0], pair[1]).to_instance(scale));
+        }
+        groups
+    }
+}
@@ -0,0 +1,184 @@
+use crate::Shape;
+use crate::parser::{obj::parse_obj, ply::parse_ply, stl::parse_stl};
+use crate::utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle};
+use glam::{Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MeshError {
+    #[error("Index count {0} is not a multiple of 3")]
+    IndexCount(usize),
+    #[error("Vertex index {0} out of range for {1} vertices")]
+    IndexOutOfRange(u32, usize),
+    #[error("Vertex index {0} is not a non-negative integer")]
+    InvalidIndex(f64),
+}
+
+/// User supplied triangle mesh, e.g. a pocket surface, a segmentation or a CAD part.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    /// Three vertex indices per triangle, counter-clockwise when seen from outside.
+    pub indices: Vec<u32>,
+    /// Per-vertex RGBA colors; `style.color` overrides them when set.
+    pub colors: Option<Vec<Vec4>>,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Mesh {
+    fn interpolate(&self, other: &Self, t: f32, logger: impl Logger) -> Self {
+        if self.vertices.len() != other.vertices.len() {
+            logger.warn(format!(
+                "Mesh interpolation skipped: vertex count differs (self: {}, other: {})",
+                self.vertices.len(),
+                other.vertices.len()
+            ));
+            return self.clone();
+        }
+
+        let colors = match (&self.colors, &other.colors) {
+            (Some(a), Some(b)) => Some(a.iter().zip(b).map(|(a, b)| a.lerp(*b, t)).collect()),
+            _ => self.colors.clone(),
+        };
+
+        Self {
+            vertices: self
+                .vertices
+                .iter()
+                .zip(&other.vertices)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+            normals: self
+                .normals
+                .iter()
+                .zip(&other.normals)
+                .map(|(a, b)| a.lerp(*b, t).normalize_or_zero())
+                .collect(),
+            colors,
+            ..self.clone()
+        }
+    }
+}
+
+impl Into<Shape> for Mesh {
+    fn into(self) -> Shape {
+        Shape::Mesh(self)
+    }
+}
+
+impl VisualShape for Mesh {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl TryFrom<MeshData> for Mesh {
+    type Error = MeshError;
+
+    fn try_from(data: MeshData) -> Result<Self, MeshError> {
+        Self::new(data)
+    }
+}
+
+impl Mesh {
+    /// Wraps `data`; normals are computed from the triangles when missing. Fails when
+    /// the indices do not form whole triangles or point past the vertices.
+    pub fn new(mut data: MeshData) -> Result<Self, MeshError> {
+        if !data.indices.len().is_multiple_of(3) {
+            return Err(MeshError::IndexCount(data.indices.len()));
+        }
+        if let Some(&i) = data
+            .indices
+            .iter()
+            .find(|&&i| i as usize >= data.vertices.len())
+        {
+            return Err(MeshError::IndexOutOfRange(i, data.vertices.len()));
+        }
+        if data.normals.len() != data.vertices.len() {
+            data.compute_normals();
+        }
+        if data
+            .colors
+            .as_ref()
+            .is_some_and(|c| c.len() != data.vertices.len())
+        {
+            data.colors = None;
+        }
+
+        Ok(Self {
+            vertices: data.vertices,
+            normals: data.normals,
+            indices: data.indices,
+            colors: data.colors,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                wireframe: data.is_wireframe,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        })
+    }
+
+    /// Reads a Wavefront OBJ file.
+    pub fn from_obj(obj: &str) -> std::io::Result<Self> {
+        Self::new(parse_obj(obj)?).map_err(invalid_data)
+    }
+
+    /// Reads an ASCII or binary PLY file.
+    pub fn from_ply(bytes: &[u8]) -> std::io::Result<Self> {
+        Self::new(parse_ply(bytes)?).map_err(invalid_data)
+    }
+
+    /// Reads an ASCII or binary STL file.
+    pub fn from_stl(bytes: &[u8]) -> std::io::Result<Self> {
+        Self::new(parse_stl(bytes)?).map_err(invalid_data)
+    }
+
+    pub fn get_center(&self) -> [f32; 3] {
+        if self.vertices.is_empty() {
+            return [0.0; 3];
+        }
+        let sum: Vec3 = self.vertices.iter().sum();
+        (sum / self.vertices.len() as f32).to_array()
+    }
+
+    pub fn centered(mut self) -> Self {
+        let center = Vec3::from_array(self.get_center());
+        for v in &mut self.vertices {
+            *v -= center;
+        }
+        self
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let alpha = self.style.opacity.clamp(0.0, 1.0);
+        let colors = match (self.style.color, &self.colors) {
+            (Some(color), _) => vec![color.extend(alpha); self.vertices.len()],
+            (None, Some(colors)) => colors
+                .iter()
+                .map(|c| c.truncate().extend(c.w * alpha))
+                .collect(),
+            (None, None) => vec![Vec4::new(1.0, 1.0, 1.0, alpha); self.vertices.len()],
+        };
+
+        MeshData {
+            vertices: self.vertices.iter().map(|v| *v * scale).collect(),
+            normals: self.normals.clone(),
+            indices: self.indices.clone(),
+            colors: Some(colors),
+            transform: None,
+            is_wireframe: self.style.wireframe,
+            line_width: self.style.line_width,
+            ..Default::default()
+        }
+    }
+}
+
+fn invalid_data(e: MeshError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+}
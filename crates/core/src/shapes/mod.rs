@@ -5,6 +5,44 @@ mod stick;
 pub use stick::Stick;
 pub use stick::StickInstance;
 mod molecules;
+pub use molecules::HeteroOptions;
 pub use molecules::Molecule;
+pub use molecules::MoleculeStyle;
 mod protein;
+mod rings;
+mod sidechain;
+pub use protein::CartoonStyle;
 pub use protein::Protein;
+pub use protein::ProteinColoring;
+pub use protein::ProteinStyle;
+pub use protein::ResidueValuesError;
+mod volume;
+pub use volume::VolumeGrid;
+mod surface;
+pub use surface::Surface;
+pub use surface::SurfaceKind;
+mod isosurface;
+pub use isosurface::Isosurface;
+mod mesh;
+pub use mesh::{Mesh, MeshError};
+mod label;
+pub use label::LabelSize;
+pub use label::Labels;
+mod arrow;
+pub use arrow::Arrow;
+mod measurement;
+pub use measurement::Measurement;
+pub use measurement::MeasurementError;
+pub use measurement::MeasurementKind;
+mod unit_cell;
+pub use unit_cell::UnitCell;
+mod cuboid;
+pub use cuboid::Cuboid;
+mod cone;
+pub use cone::Cone;
+mod torus;
+pub use torus::Torus;
+mod plane;
+pub use plane::Plane;
+mod ellipsoid;
+pub use ellipsoid::Ellipsoid;
@@ -1,15 +1,33 @@
+use crate::parser::cif::Cif;
+use crate::parser::cube::Cube;
+use crate::parser::mmcif::MmCif;
+use crate::parser::pdb::Pdb;
+use crate::parser::sasa::{SasaCalculator, sasa_radius};
 use crate::parser::sdf::Sdf;
+use crate::parser::utils::AtomGeneric;
 use crate::parser::utils::BondType as SdfBondType;
+use crate::parser::utils::{ChainGeneric, ResidueGeneric, ResidueType};
+use crate::selection::{AtomSite, Selection, SelectionError, WATER_NAMES};
+use crate::shapes::rings::sssr;
 use crate::utils::InstanceGroups;
 pub use crate::utils::Logger;
 use crate::{
     Shape,
-    shapes::{sphere::Sphere, stick::Stick},
-    utils::{Interaction, Interpolatable, IntoInstanceGroups, MeshData, VisualShape, VisualStyle},
+    shapes::{
+        Ellipsoid, StickInstance, UnitCell, VolumeGrid, protein::ParseMmCifError, sphere::Sphere,
+        stick::Stick,
+    },
+    utils::{
+        Interaction, Interpolatable, IntoInstanceGroups, MeshData, VisualShape, VisualStyle,
+        blue_white_red,
+    },
 };
 use glam::Vec3;
+use kiddo::{KdTree, SquaredEuclidean};
 use na_seq::Element;
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 pub fn my_color(element: &Element) -> Vec3 {
     // 优先使用自定义颜色
@@ -46,11 +64,31 @@ pub enum BondType {
     AROMATIC = 0,
 }
 
+/// Radius in Å of the bonds and joints of [`MoleculeStyle::Stick`].
+const LICORICE_RADIUS: f32 = 0.2;
+
+/// Distance in Å of the inner line of ring double and aromatic bonds from the bond axis.
+const RING_INNER_OFFSET: f32 = 0.25;
+/// Fraction of the bond cut off at each end of the inner line.
+const RING_INNER_TRIM: f32 = 0.18;
+/// Largest ring whose double and aromatic bonds are drawn toward its center.
+const MAX_DRAWN_RING_SIZE: usize = 8;
+const AROMATIC_DASH: f32 = 0.14;
+const AROMATIC_GAP: f32 = 0.1;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MoleculeStyle {
+    /// Atoms at a fifth of their van der Waals radius, bonds drawn by bond order.
     BallAndStick,
+    /// Licorice: equal-radius bonds with rounded joints at the atoms.
     Stick,
+    /// CPK spacefill with full van der Waals radii.
     Sphere,
+    /// Thermal ellipsoids with principal-axis rings for atoms with displacement tensors.
+    Ortep,
+    /// Bonds as unlit lines colored by the element of each half; atoms without
+    /// bonds are small crosses. The cheapest style, meant for large systems.
+    Line,
 }
 
 mod element_serde {
@@ -105,12 +143,25 @@ pub struct Molecule {
     pub atom_types: Vec<Element>,
     pub atom_colors: Option<Vec<Option<Vec3>>>,
     pub atom_posits: Vec<Vec3>,
+    /// Cartesian displacement tensors in Å² (U11, U22, U33, U12, U13, U23).
+    pub atom_aniso: Option<Vec<Option<[f32; 6]>>>,
+    /// Atom names, residues and chains, when read from a structure file.
+    pub atom_sites: Option<Vec<AtomSite>>,
     pub bond_types: Vec<BondType>,
     pub bond_indices: Vec<[usize; 2]>,
     pub quality: u32,
+    /// Probability enclosed by the ellipsoids of [`MoleculeStyle::Ortep`].
+    pub ellipsoid_probability: f32,
+    /// Multiplies atom and bond radii of the instanced styles.
+    pub radius_scale: f32,
 
     pub visual_style: VisualStyle,
     pub interaction: Interaction,
+
+    /// Rings drawn with offset double and aromatic bonds; they only depend on the
+    /// bonds, so interpolated frames share them.
+    #[serde(skip)]
+    ring_cache: OnceCell<Vec<Vec<usize>>>,
 }
 
 impl Interpolatable for Molecule {
@@ -190,11 +241,16 @@ impl Interpolatable for Molecule {
             atom_types: self.atom_types.clone(),
             atom_colors: atom_colors,
             atom_posits: atoms,
+            atom_aniso: self.atom_aniso.clone(),
+            atom_sites: self.atom_sites.clone(),
             bond_types: self.bond_types.clone(),
             bond_indices: self.bond_indices.clone(),
             quality: ((self.quality as f32) * (1.0 - t) + (other.quality as f32) * t) as u32,
+            ellipsoid_probability: self.ellipsoid_probability,
+            radius_scale: self.radius_scale * (1.0 - t) + other.radius_scale * t,
             visual_style: self.visual_style.clone(),
             interaction: self.interaction.clone(),
+            ring_cache: self.ring_cache.clone(),
         }
     }
 }
@@ -212,6 +268,49 @@ pub enum ParseSdfError {
     ParsingError(String),
 }
 
+#[derive(Error, Debug)]
+pub enum ParseCubeError {
+    #[error("Failed to parse cube data: '{0}'")]
+    ParsingError(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ParsePdbError {
+    #[error("Failed to parse PDB data: '{0}'")]
+    ParsingError(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ParseCifError {
+    #[error("Failed to parse CIF data: '{0}'")]
+    ParsingError(String),
+}
+
+/// Which groups [`Molecule::hetero_groups`] returns besides ligands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeteroOptions {
+    pub water: bool,
+    /// Single-atom groups such as metal and halide ions.
+    pub ions: bool,
+}
+
+impl Default for HeteroOptions {
+    fn default() -> Self {
+        Self {
+            water: false,
+            ions: true,
+        }
+    }
+}
+
+/// Drops alternate conformations other than the first (blank or `A`).
+fn first_conformation(atoms: Vec<AtomGeneric>) -> Vec<AtomGeneric> {
+    atoms
+        .into_iter()
+        .filter(|a| a.alt_conformation_id.as_deref().is_none_or(|id| id == "A"))
+        .collect()
+}
+
 impl Molecule {
     pub fn from_sdf(sdf: &str) -> Result<Self, ParseSdfError> {
         let molecule_data =
@@ -266,15 +365,188 @@ impl Molecule {
             atom_types,
             atom_posits,
             atom_colors,
+            atom_aniso: None,
+            atom_sites: None,
             bond_types,
             bond_indices,
             quality: 6,
+            ellipsoid_probability: 0.5,
+            radius_scale: 1.0,
+            visual_style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+            ring_cache: OnceCell::new(),
+        })
+    }
+
+    /// Molecule from bare atoms; bonds are guessed from covalent radii.
+    pub fn from_atoms(atoms: &[AtomGeneric]) -> Self {
+        let atom_posits: Vec<Vec3> = atoms.iter().map(|a| a.posit).collect();
+        let atom_types: Vec<Element> = atoms.iter().map(|a| a.element).collect();
+        let bond_indices = perceive_bonds(&atom_types, &atom_posits);
+        let atom_aniso = atoms
+            .iter()
+            .any(|a| a.aniso.is_some())
+            .then(|| atoms.iter().map(|a| a.aniso).collect());
+
+        Self {
+            style: MoleculeStyle::BallAndStick,
+            atom_types,
+            atom_posits,
+            atom_colors: None,
+            atom_aniso,
+            atom_sites: None,
+            bond_types: vec![BondType::SINGLE; bond_indices.len()],
+            bond_indices,
+            quality: 6,
+            ellipsoid_probability: 0.5,
+            radius_scale: 1.0,
             visual_style: VisualStyle {
                 opacity: 1.0,
                 visible: true,
                 ..Default::default()
             },
             interaction: Default::default(),
+            ring_cache: OnceCell::new(),
+        }
+    }
+
+    /// Reads a Gaussian cube file into its molecule and first volumetric data set.
+    pub fn from_cube(cube: &str) -> Result<(Self, VolumeGrid), ParseCubeError> {
+        let cube = Cube::new(cube).map_err(|e| ParseCubeError::ParsingError(e.to_string()))?;
+        let volume = cube
+            .volumes
+            .into_iter()
+            .next()
+            .ok_or_else(|| ParseCubeError::ParsingError("No volumetric data".to_string()))?;
+        Ok((Self::from_atoms(&cube.atoms), volume))
+    }
+
+    /// Reads the first model of a PDB file, keeping ANISOU tensors.
+    pub fn from_pdb(pdb: &str) -> Result<Self, ParsePdbError> {
+        let pdb = Pdb::new(pdb).map_err(|e| ParsePdbError::ParsingError(e.to_string()))?;
+        Ok(Self::from_structure(pdb.atoms, &pdb.residues, &pdb.chains))
+    }
+
+    /// Reads all atoms of an mmCIF file, keeping `_atom_site_anisotrop` tensors.
+    pub fn from_mmcif(mmcif: &str) -> Result<Self, ParseMmCifError> {
+        let mmcif = MmCif::new(mmcif).map_err(|e| ParseMmCifError::ParsingError(e.to_string()))?;
+        Ok(Self::from_structure(
+            mmcif.atoms,
+            &mmcif.residues,
+            &mmcif.chains,
+        ))
+    }
+
+    /// Atoms of a macromolecular file, first conformation only, with their sites.
+    fn from_structure(
+        atoms: Vec<AtomGeneric>,
+        residues: &[ResidueGeneric],
+        chains: &[ChainGeneric],
+    ) -> Self {
+        let atoms = first_conformation(atoms);
+        Self {
+            atom_sites: Some(AtomSite::from_atoms(&atoms, residues, chains)),
+            ..Self::from_atoms(&atoms)
+        }
+    }
+
+    /// Hetero groups of a structure: ligands, modified residues and ions, one
+    /// molecule per residue, plus all waters as a single molecule if requested.
+    /// Bonds are perceived from distances, so bond orders are not known.
+    pub fn hetero_groups(mmcif: &MmCif, options: HeteroOptions) -> Vec<Self> {
+        // 位点表只建一次，各组按原子下标取用
+        let sites = AtomSite::from_atoms(&mmcif.atoms, &mmcif.residues, &mmcif.chains);
+        let by_serial: HashMap<u32, usize> = mmcif
+            .atoms
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.serial_number, i))
+            .collect();
+        let group = |indices: &[usize]| {
+            let atoms: Vec<AtomGeneric> = indices.iter().map(|&i| mmcif.atoms[i].clone()).collect();
+            Self {
+                atom_sites: Some(indices.iter().map(|&i| sites[i].clone()).collect()),
+                ..Self::from_atoms(&atoms)
+            }
+        };
+
+        let mut groups = Vec::new();
+        let mut waters = Vec::new();
+        for residue in &mmcif.residues {
+            let indices: Vec<usize> = residue
+                .atom_sns
+                .iter()
+                .filter_map(|sn| by_serial.get(sn).copied())
+                .filter(|&i| {
+                    let atom = &mmcif.atoms[i];
+                    atom.hetero
+                        && atom
+                            .alt_conformation_id
+                            .as_deref()
+                            .is_none_or(|id| id == "A")
+                })
+                .collect();
+            if indices.is_empty() {
+                continue;
+            }
+
+            let is_water = match &residue.res_type {
+                ResidueType::Water => true,
+                ResidueType::Other(name) => WATER_NAMES.contains(&name.to_uppercase().as_str()),
+                ResidueType::AminoAcid(_) => false,
+            };
+            let heavy = indices
+                .iter()
+                .filter(|&&i| mmcif.atoms[i].element != Element::Hydrogen)
+                .count();
+            if is_water {
+                if options.water {
+                    waters.extend(indices);
+                }
+            } else if heavy == 1 {
+                if options.ions {
+                    groups.push(group(&indices));
+                }
+            } else {
+                groups.push(group(&indices));
+            }
+        }
+        if !waters.is_empty() {
+            groups.push(group(&waters));
+        }
+        groups
+    }
+
+    /// [`Molecule::hetero_groups`] of an mmCIF file.
+    pub fn hetero_groups_from_mmcif(
+        mmcif: &str,
+        options: HeteroOptions,
+    ) -> Result<Vec<Self>, ParseMmCifError> {
+        let mmcif = MmCif::new(mmcif).map_err(|e| ParseMmCifError::ParsingError(e.to_string()))?;
+        Ok(Self::hetero_groups(&mmcif, options))
+    }
+
+    /// Reads the asymmetric unit of a small-molecule CIF file, keeping
+    /// `_atom_site_aniso` tensors.
+    pub fn from_cif(cif: &str) -> Result<Self, ParseCifError> {
+        let cif = Cif::new(cif).map_err(|e| ParseCifError::ParsingError(e.to_string()))?;
+        // 小分子 CIF 没有残基和链，只保留原子标签
+        let sites = cif
+            .atoms
+            .iter()
+            .map(|a| AtomSite {
+                name: a.type_in_res_general.clone().unwrap_or_default(),
+                hetero: true,
+                ..Default::default()
+            })
+            .collect();
+        Ok(Self {
+            atom_sites: Some(sites),
+            ..Self::from_atoms(&cif.atoms)
         })
     }
 
@@ -313,13 +585,329 @@ impl Molecule {
         self
     }
 
+    /// Replicates the molecule into an NxMxK block of neighboring cells.
+    ///
+    /// Copies are ordered cell by cell, c fastest, so atom `i` of cell number
+    /// `n` has index `n * atom_count + i`.
+    pub fn supercell(&self, cell: &UnitCell, repeat: [usize; 3]) -> Self {
+        let n_atoms = self.atom_posits.len();
+        let mut result = Self {
+            atom_types: Vec::new(),
+            atom_colors: self.atom_colors.as_ref().map(|_| Vec::new()),
+            atom_aniso: self.atom_aniso.as_ref().map(|_| Vec::new()),
+            atom_sites: self.atom_sites.as_ref().map(|_| Vec::new()),
+            atom_posits: Vec::new(),
+            bond_types: Vec::new(),
+            bond_indices: Vec::new(),
+            ring_cache: OnceCell::new(),
+            ..self.clone()
+        };
+
+        let [n, m, k] = repeat.map(|r| r.max(1) as i32);
+        for (copy, offset) in (0..n)
+            .flat_map(|i| (0..m).flat_map(move |j| (0..k).map(move |k| [i, j, k])))
+            .map(|ijk| cell.translation(ijk))
+            .enumerate()
+        {
+            let base = copy * n_atoms;
+            result
+                .atom_posits
+                .extend(self.atom_posits.iter().map(|p| *p + offset));
+            result.atom_types.extend_from_slice(&self.atom_types);
+            if let (Some(out), Some(colors)) = (&mut result.atom_colors, &self.atom_colors) {
+                out.extend_from_slice(colors);
+            }
+            if let (Some(out), Some(aniso)) = (&mut result.atom_aniso, &self.atom_aniso) {
+                out.extend_from_slice(aniso);
+            }
+            if let (Some(out), Some(sites)) = (&mut result.atom_sites, &self.atom_sites) {
+                out.extend_from_slice(sites);
+            }
+            result.bond_types.extend_from_slice(&self.bond_types);
+            result
+                .bond_indices
+                .extend(self.bond_indices.iter().map(|[a, b]| [a + base, b + base]));
+        }
+        result
+    }
+
+    /// Indices of the atoms matching a selection expression, see [`crate::selection`].
+    pub fn select(&self, expr: &str) -> Result<Vec<usize>, SelectionError> {
+        Ok(Selection::parse(expr)?.select_molecule(self))
+    }
+
+    /// New molecule with only the given atoms and the bonds between them.
+    /// Atoms keep their relative order; out-of-range indices are ignored.
+    pub fn subset(&self, indices: &[usize]) -> Self {
+        let mut selected = vec![false; self.atom_posits.len()];
+        for &i in indices {
+            if let Some(s) = selected.get_mut(i) {
+                *s = true;
+            }
+        }
+        // 旧索引 -> 新索引
+        let kept: Vec<usize> = (0..selected.len()).filter(|&i| selected[i]).collect();
+        let mut keep = vec![None; selected.len()];
+        for (new, &old) in kept.iter().enumerate() {
+            keep[old] = Some(new);
+        }
+
+        fn pick<T: Clone>(values: &[T], kept: &[usize]) -> Vec<T> {
+            kept.iter().map(|&i| values[i].clone()).collect()
+        }
+        let (bond_types, bond_indices) = self
+            .bond_indices
+            .iter()
+            .enumerate()
+            .filter_map(|(b, &[i, j])| {
+                let (i, j) = (keep[i]?, keep[j]?);
+                let bond_type = self.bond_types.get(b).copied().unwrap_or(BondType::SINGLE);
+                Some((bond_type, [i, j]))
+            })
+            .unzip();
+        Self {
+            atom_types: pick(&self.atom_types, &kept),
+            atom_posits: pick(&self.atom_posits, &kept),
+            atom_colors: self.atom_colors.as_deref().map(|v| pick(v, &kept)),
+            atom_aniso: self.atom_aniso.as_deref().map(|v| pick(v, &kept)),
+            atom_sites: self.atom_sites.as_deref().map(|v| pick(v, &kept)),
+            bond_types,
+            bond_indices,
+            ring_cache: OnceCell::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Colors the given atoms, leaving the others as they are.
+    pub fn color_atoms(mut self, indices: &[usize], color: [f32; 3]) -> Self {
+        let n = self.atom_posits.len();
+        let colors = self.atom_colors.get_or_insert_with(|| vec![None; n]);
+        for &i in indices {
+            if let Some(c) = colors.get_mut(i) {
+                *c = Some(color.into());
+            }
+        }
+        self
+    }
+
+    /// Sets how atoms and bonds are drawn, see [`MoleculeStyle`].
+    pub fn style(mut self, style: MoleculeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Scales atom and bond radii, e.g. 0.8 for a slimmer spacefill.
+    pub fn radius_scale(mut self, scale: f32) -> Self {
+        self.radius_scale = scale;
+        self
+    }
+
+    /// Draws atoms as thermal ellipsoids enclosing them with `probability`
+    /// (0.5 in most ORTEP plots). Atoms without a tensor stay small spheres.
+    pub fn ortep(mut self, probability: f32) -> Self {
+        self.style = MoleculeStyle::Ortep;
+        self.ellipsoid_probability = probability;
+        self
+    }
+
+    /// Draws bonds as lines `width` pixels wide, see [`MoleculeStyle::Line`].
+    pub fn line(mut self, width: f32) -> Self {
+        self.style = MoleculeStyle::Line;
+        self.visual_style.line_width = Some(width);
+        self
+    }
+
+    /// Whether the molecule is drawn as lines, by style or through `wireframe(true)`.
+    fn is_line(&self) -> bool {
+        self.style == MoleculeStyle::Line || self.visual_style.wireframe
+    }
+
+    /// Ellipsoid of atom `index`, if it has a displacement tensor.
+    pub fn atom_ellipsoid(&self, index: usize) -> Option<Ellipsoid> {
+        let u = self.atom_aniso.as_ref()?.get(index).copied().flatten()?;
+        Some(Ellipsoid::from_displacement(
+            self.atom_posits[index].to_array(),
+            u,
+            self.ellipsoid_probability,
+        ))
+    }
+
     pub fn reset_color(mut self) -> Self {
         self.style_mut().color = None;
         self
     }
 
-    pub fn to_mesh(&self, _scale: f32) -> MeshData {
-        MeshData::default()
+    /// Smallest set of smallest rings (SSSR), each as atom indices in ring order.
+    pub fn rings(&self) -> Vec<Vec<usize>> {
+        sssr(self.atom_posits.len(), &self.bond_indices, None)
+    }
+
+    /// Center of the smallest ring through each ring bond, keyed by `[min, max]` atom index.
+    fn ring_bond_centers(&self) -> HashMap<[usize; 2], Vec3> {
+        let mut centers = HashMap::new();
+        // sssr 按环大小排序，先插入的就是最小环
+        let rings = self.ring_cache.get_or_init(|| {
+            sssr(
+                self.atom_posits.len(),
+                &self.bond_indices,
+                Some(MAX_DRAWN_RING_SIZE),
+            )
+        });
+        for ring in rings {
+            let center =
+                ring.iter().map(|&i| self.atom_posits[i]).sum::<Vec3>() / ring.len() as f32;
+            for k in 0..ring.len() {
+                let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                centers.entry([a.min(b), a.max(b)]).or_insert(center);
+            }
+        }
+        centers
+    }
+
+    /// A ring double or aromatic bond: a bond on the axis plus a shorter inner line
+    /// toward the ring center, dashed when aromatic.
+    fn ring_bond_sticks(
+        &self,
+        [a, b]: [usize; 2],
+        center: Vec3,
+        aromatic: bool,
+        radius: f32,
+        scale: f32,
+    ) -> Vec<StickInstance> {
+        let (pa, pb) = (self.atom_posits[a], self.atom_posits[b]);
+        let dir = (pb - pa).normalize_or_zero();
+        let mid = (pa + pb) * 0.5;
+        let to_center = center - mid;
+        let inward = (to_center - dir * to_center.dot(dir)).normalize_or_zero() * RING_INNER_OFFSET;
+        let inner_a = pa.lerp(pb, RING_INNER_TRIM) + inward;
+        let inner_b = pa.lerp(pb, 1.0 - RING_INNER_TRIM) + inward;
+        let inner_mid = mid + inward;
+
+        let mut sticks = Vec::new();
+        for (end, inner_end, atom) in [(pa, inner_a, a), (pb, inner_b, b)] {
+            let color: [f32; 3] = self.bond_color(atom).into();
+            let outer = Stick::new(end.to_array(), mid.to_array(), radius)
+                .color(color)
+                .opacity(self.visual_style.opacity);
+            sticks.push(outer.to_instance(scale));
+
+            let inner = Stick::new(inner_end.to_array(), inner_mid.to_array(), radius * 0.75)
+                .color(color)
+                .opacity(self.visual_style.opacity);
+            if aromatic {
+                sticks.extend(inner.to_dashed_instances(scale, AROMATIC_DASH, AROMATIC_GAP));
+            } else {
+                sticks.push(inner.to_instance(scale));
+            }
+        }
+        sticks
+    }
+
+    /// Per-atom solvent accessible surface area in Å² (Shrake–Rupley).
+    pub fn sasa(&self) -> Vec<f32> {
+        let radii: Vec<f32> = self.atom_types.iter().map(sasa_radius).collect();
+        SasaCalculator::new().compute(&self.atom_posits, &radii)
+    }
+
+    /// Colors atoms by arbitrary per-atom values (blue = lowest, red = highest).
+    pub fn color_by_values(mut self, values: &[f32]) -> Self {
+        let (min, max) = values
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        let range = (max - min).max(1e-6);
+
+        self.atom_colors = Some(
+            (0..self.atom_posits.len())
+                .map(|i| values.get(i).map(|v| blue_white_red((v - min) / range)))
+                .collect(),
+        );
+        self
+    }
+
+    /// Colors atoms by their solvent accessible surface area.
+    pub fn color_by_sasa(self) -> Self {
+        let sasa = self.sasa();
+        self.color_by_values(&sasa)
+    }
+
+    /// Lines of [`MoleculeStyle::Line`] and ellipsoids of [`MoleculeStyle::Ortep`];
+    /// other styles are drawn as instances.
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        if self.is_line() {
+            return self.to_line_mesh(scale);
+        }
+        let mut mesh = MeshData::default();
+        if self.style != MoleculeStyle::Ortep {
+            return mesh;
+        }
+        for i in 0..self.atom_posits.len() {
+            if let Some(ellipsoid) = self.atom_ellipsoid(i) {
+                let ellipsoid = ellipsoid
+                    .color(self.get_atom_colors(i).into())
+                    .opacity(self.visual_style.opacity);
+                mesh.append(&ellipsoid.to_mesh(scale));
+            }
+        }
+        mesh
+    }
+
+    fn to_line_mesh(&self, scale: f32) -> MeshData {
+        let alpha = self.visual_style.opacity.clamp(0.0, 1.0);
+        let mut mesh = MeshData {
+            line_width: self.visual_style.line_width,
+            ..Default::default()
+        };
+        let push_segment = |mesh: &mut MeshData, a: Vec3, b: Vec3, color: Vec3| {
+            let base = mesh.vertices.len() as u32;
+            mesh.vertices.extend([a * scale, b * scale]);
+            // 零法线表示不做光照
+            mesh.normals.extend([Vec3::ZERO; 2]);
+            mesh.colors
+                .get_or_insert_with(Vec::new)
+                .extend([color.extend(alpha); 2]);
+            mesh.line_indices.extend([base, base + 1]);
+        };
+
+        let mut bonded = vec![false; self.atom_posits.len()];
+        for &[a, b] in &self.bond_indices {
+            let (pa, pb) = (self.atom_posits[a], self.atom_posits[b]);
+            let mid = (pa + pb) * 0.5;
+            push_segment(&mut mesh, pa, mid, self.get_atom_colors(a));
+            push_segment(&mut mesh, mid, pb, self.get_atom_colors(b));
+            bonded[a] = true;
+            bonded[b] = true;
+        }
+
+        // 孤立原子（离子、无氢的水）画成小十字
+        const CROSS: f32 = 0.25;
+        for (i, &p) in self.atom_posits.iter().enumerate() {
+            if bonded[i] {
+                continue;
+            }
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                push_segment(
+                    &mut mesh,
+                    p - axis * CROSS,
+                    p + axis * CROSS,
+                    self.get_atom_colors(i),
+                );
+            }
+        }
+        mesh
+    }
+
+    /// Color of the bond half at atom `index`; carbon is lighter than in
+    /// [`Molecule::get_atom_colors`] so bonds stand out against the atoms.
+    fn bond_color(&self, index: usize) -> Vec3 {
+        if let Some(Some(c)) = self.atom_colors.as_ref().and_then(|c| c.get(index)) {
+            return *c;
+        }
+        self.visual_style
+            .color
+            .unwrap_or_else(|| match self.atom_types[index] {
+                Element::Carbon => Vec3::new(0.75, 0.75, 0.75),
+                ref e => my_color(e),
+            })
     }
 
     pub fn get_atom_colors(&self, index: usize) -> Vec3 {
@@ -340,20 +928,84 @@ impl Molecule {
     }
 }
 
+/// Bonds between atoms closer than the sum of their covalent radii plus a tolerance.
+fn perceive_bonds(types: &[Element], posits: &[Vec3]) -> Vec<[usize; 2]> {
+    const TOLERANCE: f32 = 0.45;
+    let radius = |e: &Element| match e.covalent_radius() as f32 {
+        r if r > 0.0 => r,
+        _ => 0.75,
+    };
+    let max_r = types.iter().map(radius).fold(0.0, f32::max);
+
+    let mut tree: KdTree<f32, 3> = KdTree::new();
+    for (i, p) in posits.iter().enumerate() {
+        tree.add(&p.to_array(), i as u64);
+    }
+
+    let mut bonds = Vec::new();
+    for (i, p) in posits.iter().enumerate() {
+        let cutoff = radius(&types[i]) + max_r + TOLERANCE;
+        let mut neighbors =
+            tree.within_unsorted::<SquaredEuclidean>(&p.to_array(), cutoff * cutoff);
+        neighbors.sort_by_key(|nb| nb.item);
+        for nb in neighbors {
+            let j = nb.item as usize;
+            let max_d = radius(&types[i]) + radius(&types[j]) + TOLERANCE;
+            // 0.4 Å 以内视为重叠原子
+            if j > i && nb.distance > 0.16 && nb.distance < max_d * max_d {
+                bonds.push([i, j]);
+            }
+        }
+    }
+    bonds
+}
+
 impl IntoInstanceGroups for Molecule {
     fn to_instance_group(&self, scale: f32) -> InstanceGroups {
         let mut groups = InstanceGroups::default();
+        if self.is_line() {
+            return groups;
+        }
 
+        let ortep = self.style == MoleculeStyle::Ortep;
         for (i, pos) in self.atom_posits.iter().enumerate() {
-            let sphere_instance = Sphere::new(
-                pos.to_array(),
-                self.atom_types.get(i).map(|x| my_radius(x) * 0.2).unwrap(),
-            )
-            .color(self.get_atom_colors(i).into())
-            .opacity(self.visual_style.opacity);
+            if ortep && let Some(ellipsoid) = self.atom_ellipsoid(i) {
+                groups
+                    .sticks
+                    .extend(ortep_rings(&ellipsoid, scale, self.visual_style.opacity));
+                continue;
+            }
+            let element = &self.atom_types[i];
+            let (radius, color) = match self.style {
+                MoleculeStyle::Sphere => (my_radius(element), self.get_atom_colors(i)),
+                // 关节球与键同半径同颜色，接缝处才是圆滑的
+                MoleculeStyle::Stick => (LICORICE_RADIUS, self.bond_color(i)),
+                // ORTEP 图中没有各向异性参数的原子（通常是氢）画成小球
+                MoleculeStyle::Ortep => (my_radius(element) * 0.1, self.get_atom_colors(i)),
+                _ => (my_radius(element) * 0.2, self.get_atom_colors(i)),
+            };
+            let sphere_instance = Sphere::new(pos.to_array(), radius * self.radius_scale)
+                .color(color.into())
+                .opacity(self.visual_style.opacity);
 
             groups.spheres.push(sphere_instance.to_instance(scale));
         }
+        if self.style == MoleculeStyle::Sphere {
+            return groups;
+        }
+
+        // 环上的双键和芳香键朝环心偏移，需要先知道每条键所在的最小环
+        let by_order = self.style != MoleculeStyle::Stick;
+        let ring_centers = if by_order
+            && self
+                .bond_types
+                .iter()
+                .any(|t| matches!(t, BondType::DOUBLE | BondType::AROMATIC))
+        {
+            self.ring_bond_centers()
+        } else {
+            HashMap::new()
+        };
 
         for (i, bond) in self.bond_indices.iter().enumerate() {
             let [a, b] = bond;
@@ -458,34 +1110,37 @@ impl IntoInstanceGroups for Molecule {
             // 偏移距离（可调）
             let d = 0.22;
 
-            // 颜色和半径与原来一致
-            let color_a = self.visual_style.color.unwrap_or(
-                self.atom_types
-                    .get(*a)
-                    .map(|x| match x {
-                        Element::Carbon => Vec3::new(0.75, 0.75, 0.75),
-                        _ => my_color(x),
-                    })
-                    .unwrap(),
-            );
-            let color_b = self.visual_style.color.unwrap_or(
-                self.atom_types
-                    .get(*b)
-                    .map(|x| match x {
-                        Element::Carbon => Vec3::new(0.75, 0.75, 0.75),
-                        _ => my_color(x),
-                    })
-                    .unwrap(),
-            );
-
-            // 根据键类型生成多个 stick
-            let (num_sticks, radius) = match bond_type {
+            let color_a = self.bond_color(*a);
+            let color_b = self.bond_color(*b);
+
+            // 根据键类型生成多个 stick；licorice 不区分键级
+            let (num_sticks, mut radius) = match bond_type {
+                _ if self.style == MoleculeStyle::Stick => (1, LICORICE_RADIUS),
                 BondType::SINGLE => (1, 0.135),
                 BondType::DOUBLE => (2, 0.09),
                 BondType::TRIPLE => (3, 0.05),
                 BondType::AROMATIC => (2, 0.09),
                 _ => (1, 0.05), // aromatic等以后再处理
             };
+            if ortep {
+                radius *= 0.5;
+            }
+            radius *= self.radius_scale;
+
+            if by_order
+                && matches!(bond_type, BondType::DOUBLE | BondType::AROMATIC)
+                && let Some(center) = ring_centers.get(&[(*a).min(*b), (*a).max(*b)])
+            {
+                let aromatic = *bond_type == BondType::AROMATIC;
+                groups.sticks.extend(self.ring_bond_sticks(
+                    [*a, *b],
+                    *center,
+                    aromatic,
+                    radius,
+                    scale,
+                ));
+                continue;
+            }
 
             for k in 0..num_sticks {
                 let offset_mul = (k as f32 - (num_sticks - 1) as f32 * 0.5) * d;
@@ -536,6 +1191,33 @@ impl IntoInstanceGroups for Molecule {
     }
 }
 
+/// Dark rings along the three principal planes of a thermal ellipsoid.
+fn ortep_rings(ellipsoid: &Ellipsoid, scale: f32, opacity: f32) -> Vec<StickInstance> {
+    const SEGMENTS: usize = 32;
+    let center = Vec3::from(ellipsoid.center);
+    let [a, b, c] = ellipsoid.principal_axes();
+    // 环略大于椭球面，避免被椭球遮住
+    let radius = 0.015 * scale;
+    let color = [0.1, 0.1, 0.1, opacity.clamp(0.0, 1.0)];
+
+    let mut sticks = Vec::with_capacity(3 * SEGMENTS);
+    for (u, v) in [(a, b), (a, c), (b, c)] {
+        let point = |k: usize| {
+            let theta = std::f32::consts::TAU * k as f32 / SEGMENTS as f32;
+            (center + (u * theta.cos() + v * theta.sin()) * 1.01) * scale
+        };
+        for k in 0..SEGMENTS {
+            sticks.push(StickInstance::new(
+                point(k).to_array(),
+                point(k + 1).to_array(),
+                radius,
+                color,
+            ));
+        }
+    }
+    sticks
+}
+
 impl VisualShape for Molecule {
     fn style_mut(&mut self) -> &mut VisualStyle {
         &mut self.visual_style
@@ -0,0 +1,174 @@
+use crate::Arc;
+use glam::{Mat4, Quat, Vec3};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Shape,
+    shapes::{StickInstance, sphere::MeshTemplate},
+    utils::{
+        InstanceGroups, Interaction, Interpolatable, IntoInstanceGroups, Logger, MeshData,
+        VisualShape, VisualStyle,
+    },
+};
+
+/// Unit square from -0.5 to 0.5 in the XY plane. Both faces are stored
+/// because back faces are culled.
+static PLANE_TEMPLATE: Lazy<Arc<MeshTemplate>> = Lazy::new(|| {
+    let corners = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)];
+    let vertices = corners
+        .iter()
+        .chain(&corners)
+        .map(|&(x, y)| Vec3::new(x, y, 0.0))
+        .collect();
+    let normals = [Vec3::Z; 4].into_iter().chain([Vec3::NEG_Z; 4]).collect();
+
+    Arc::new(MeshTemplate {
+        vertices,
+        normals,
+        indices: vec![0, 1, 2, 0, 2, 3, 4, 6, 5, 4, 7, 6],
+    })
+});
+
+/// Flat rectangle, e.g. a membrane or mirror plane, with an optional grid.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Plane {
+    pub center: [f32; 3],
+    pub normal: [f32; 3],
+    /// Width and height of the rectangle.
+    pub size: [f32; 2],
+    /// Number of grid cells along each side, 0 for no grid.
+    pub grid: u32,
+    pub grid_color: Option<Vec3>,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Plane {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        Self {
+            center: Vec3::from(self.center)
+                .lerp(Vec3::from(other.center), t)
+                .to_array(),
+            normal: Vec3::from(self.normal)
+                .lerp(Vec3::from(other.normal), t)
+                .to_array(),
+            size: [0, 1].map(|i| self.size[i] * (1.0 - t) + other.size[i] * t),
+            ..*self
+        }
+    }
+}
+
+impl Into<Shape> for Plane {
+    fn into(self) -> Shape {
+        Shape::Plane(self)
+    }
+}
+
+impl VisualShape for Plane {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Plane {
+    pub fn new(center: [f32; 3], normal: [f32; 3], size: [f32; 2]) -> Self {
+        Self {
+            center,
+            normal,
+            size,
+            grid: 0,
+            grid_color: None,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    pub fn set_center(mut self, center: [f32; 3]) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn set_normal(mut self, normal: [f32; 3]) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn set_size(mut self, size: [f32; 2]) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Draws grid lines splitting each side into `cells` cells.
+    pub fn grid(mut self, cells: u32) -> Self {
+        self.grid = cells;
+        self
+    }
+
+    pub fn grid_color(mut self, color: [f32; 3]) -> Self {
+        self.grid_color = Some(color.into());
+        self
+    }
+
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_arc(Vec3::Z, Vec3::from(self.normal).normalize_or(Vec3::Z))
+    }
+
+    pub fn get_or_generate_plane_mesh_template() -> Arc<MeshTemplate> {
+        Arc::clone(&PLANE_TEMPLATE)
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::new(self.size[0], self.size[1], 1.0) * scale,
+            self.rotation(),
+            Vec3::from(self.center) * scale,
+        );
+        let color = self.style.color.unwrap_or(Vec3::ONE);
+        Self::get_or_generate_plane_mesh_template().to_mesh(
+            transform,
+            color.extend(self.style.opacity.clamp(0.0, 1.0)),
+            self.style.wireframe,
+        )
+    }
+}
+
+impl IntoInstanceGroups for Plane {
+    fn to_instance_group(&self, scale: f32) -> InstanceGroups {
+        let mut groups = InstanceGroups::default();
+        if self.grid == 0 || !self.style.visible {
+            return groups;
+        }
+
+        let rotation = self.rotation();
+        let center = Vec3::from(self.center);
+        let [w, h] = self.size;
+        let (u, v) = (rotation * Vec3::X * w, rotation * Vec3::Y * h);
+        let color = self
+            .grid_color
+            .unwrap_or(self.style.color.unwrap_or(Vec3::ONE) * 0.5)
+            .extend(self.style.opacity.clamp(0.0, 1.0))
+            .to_array();
+        let radius = w.max(h) * 0.002 * scale;
+
+        // 圆柱网格线中心在平面上，两面都能看到一半
+        for k in 0..=self.grid {
+            let s = k as f32 / self.grid as f32 - 0.5;
+            for (along, across) in [(u, v), (v, u)] {
+                let start = center + across * s - along * 0.5;
+                groups.sticks.push(StickInstance::new(
+                    (start * scale).to_array(),
+                    ((start + along) * scale).to_array(),
+                    radius,
+                    color,
+                ));
+            }
+        }
+        groups
+    }
+}
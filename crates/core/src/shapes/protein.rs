@@ -1,22 +1,141 @@
 use crate::Shape;
+use crate::parser::dssp::HydrogenBond;
 use crate::parser::mmcif::Chain;
 use crate::parser::mmcif::MmCif;
-use crate::parser::utils::{Residue, ResidueType::AminoAcid, SecondaryStructure};
-use crate::utils::{MeshData, VisualShape, VisualStyle};
+use crate::parser::sasa::{SasaCalculator, sasa_radius};
+use crate::parser::utils::{
+    AtomGeneric, BackboneDihedrals, MAX_CA_CA_DISTANCE, MAX_PEPTIDE_BOND_LENGTH, Residue,
+    ResidueAtom, ResidueType::AminoAcid, SecondaryStructure,
+};
+use crate::selection::{AtomSite, Selection, SelectionError};
+use crate::shapes::sidechain::sidechain_instances;
+use crate::shapes::{Sphere, Stick};
+use crate::utils::{
+    InstanceGroups, Interpolatable, IntoInstanceGroups, Logger, MeshData, VisualShape, VisualStyle,
+    blue_white_red, rainbow,
+};
 use bytemuck::{Pod, Zeroable};
 use glam::{Quat, Vec3, Vec4};
-use na_seq::AtomTypeInRes;
+use na_seq::{AaCategory, AaIdent, AtomTypeInRes};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use wide::f32x8;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Protein {
     pub chains: Vec<Chain>,
     pub center: Vec3,
+    pub show_hbonds: bool,
+    pub show_sidechains: bool,
+    /// Draw chain breaks as dashed lines between the CA atoms around the gap.
+    pub show_gaps: bool,
+    /// `(chain, residue)` indices whose side chains are drawn; all when `None`.
+    pub sidechain_residues: Option<Vec<(usize, usize)>>,
+    pub coloring: ProteinColoring,
+    pub representation: ProteinStyle,
+    /// Per-residue values driving the [`ProteinStyle::Putty`] radius; CA B-factors when `None`.
+    pub putty_values: Option<Vec<f32>>,
+    pub cartoon: CartoonStyle,
 
     pub style: VisualStyle,
 }
 
+/// Tessellation and cross-section dimensions (Å) of the backbone mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CartoonStyle {
+    /// Spline samples between consecutive residues.
+    pub pts_per_res: usize,
+    /// Vertices around round cross-sections (helix, coil, tube, putty).
+    pub ring_segments: usize,
+    pub helix_width: f32,
+    pub helix_thickness: f32,
+    pub sheet_width: f32,
+    pub sheet_thickness: f32,
+    pub coil_radius: f32,
+    /// Lower the detail of large assemblies to stay within [`MAX_CARTOON_VERTICES`].
+    pub auto_lod: bool,
+}
+
+impl Default for CartoonStyle {
+    fn default() -> Self {
+        Self {
+            pts_per_res: 5,
+            ring_segments: 32,
+            helix_width: 2.0,
+            helix_thickness: 0.5,
+            sheet_width: 2.0,
+            sheet_thickness: 0.4,
+            coil_radius: 0.2,
+            auto_lod: true,
+        }
+    }
+}
+
+/// Vertex budget of the backbone mesh when [`CartoonStyle::auto_lod`] is on.
+pub const MAX_CARTOON_VERTICES: usize = 2_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ProteinStyle {
+    /// Helices, sheet arrows and coil following the secondary structure.
+    #[default]
+    Cartoon,
+    /// Round tube of constant radius along the backbone spline.
+    Tube,
+    /// Straight sticks between consecutive CA atoms.
+    Trace,
+    /// Tube whose radius follows a per-residue value such as the B-factor or RMSF.
+    Putty,
+}
+
+/// How the cartoon is colored. Colors are blended along the spline between residues.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ProteinColoring {
+    /// `style.color`, or white.
+    #[default]
+    Uniform,
+    Chain,
+    SecondaryStructure,
+    /// Blue at the N terminus to red at the C terminus of each chain.
+    Rainbow,
+    /// Basic blue, acidic red, polar green, hydrophobic gray.
+    ResidueType,
+    /// Kyte–Doolittle hydropathy, hydrophilic blue to hydrophobic red.
+    Hydrophobicity,
+    /// One value per residue, chain after chain (blue = lowest, red = highest).
+    /// Residues without a value are white; [`Protein::color_by_values`] checks the count.
+    Values(Vec<f32>),
+}
+
+const CHAIN_COLORS: [[f32; 3]; 8] = [
+    [0.35, 0.65, 1.0],
+    [1.0, 0.55, 0.2],
+    [0.4, 0.85, 0.4],
+    [0.95, 0.35, 0.45],
+    [0.7, 0.5, 0.95],
+    [0.95, 0.85, 0.3],
+    [0.3, 0.85, 0.85],
+    [0.95, 0.55, 0.8],
+];
+const TUBE_RADIUS: f32 = 0.3;
+const TRACE_RADIUS: f32 = 0.2;
+const GAP_COLOR: [f32; 3] = [0.6, 0.6, 0.6];
+const GAP_RADIUS: f32 = 0.1;
+const GAP_DASH: f32 = 0.5;
+const GAP_GAP: f32 = 0.4;
+const PUTTY_MIN_RADIUS: f32 = 0.2;
+const PUTTY_MAX_RADIUS: f32 = 1.5;
+
+const HELIX_COLOR: [f32; 3] = [0.9, 0.25, 0.45];
+const SHEET_COLOR: [f32; 3] = [1.0, 0.8, 0.15];
+const COIL_COLOR: [f32; 3] = [0.85, 0.85, 0.85];
+
+const HBOND_COLOR: [f32; 3] = [1.0, 0.85, 0.1];
+const HBOND_RADIUS: f32 = 0.06;
+const HBOND_DASH: f32 = 0.25;
+const HBOND_GAP: f32 = 0.2;
+/// N-H bond length (Å) used when the file has no amide hydrogen.
+const AMIDE_NH_LENGTH: f32 = 1.01;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -25,6 +144,12 @@ pub enum ParseMmCifError {
     ParsingError(String),
 }
 
+#[derive(Error, Debug)]
+pub enum ResidueValuesError {
+    #[error("Expected one value per residue ({expected}), got {found}")]
+    CountMismatch { expected: usize, found: usize },
+}
+
 impl Protein {
     pub fn from_mmcif(sdf: &str) -> Result<Self, ParseMmCifError> {
         let protein_data =
@@ -36,9 +161,14 @@ impl Protein {
         let mut chains = Vec::new();
         let mut centers = Vec::new();
         let mut residue_index = 0;
+        // 序号不一定连续（例如只保留 CA 的文件），按序号查找原子
+        let by_serial: HashMap<u32, &AtomGeneric> =
+            mmcif.atoms.iter().map(|a| (a.serial_number, a)).collect();
 
         for chain in mmcif.chains {
             let mut residues = Vec::new();
+            // 每个残基缺少的主链原子 [N, C, O]
+            let mut missing = Vec::new();
 
             for residue_sns in chain.residue_sns {
                 let residue = &mmcif.residues[residue_index];
@@ -51,8 +181,8 @@ impl Protein {
                 let mut c_opt = None;
                 let mut n_opt = None;
                 let mut o_opt = None;
-                for atom_sn in &residue.atom_sns {
-                    let atom = &mmcif.atoms[*atom_sn as usize - 1];
+                let mut b_factor = None;
+                for atom in residue.atom_sns.iter().filter_map(|sn| by_serial.get(sn)) {
                     if let Some(atom_type_in_res) = &atom.type_in_res {
                         if *atom_type_in_res == AtomTypeInRes::C {
                             c_opt = Some(atom.posit);
@@ -62,6 +192,7 @@ impl Protein {
                         }
                         if *atom_type_in_res == AtomTypeInRes::CA {
                             ca_opt = Some(atom.posit);
+                            b_factor = atom.b_factor;
                         }
                         if *atom_type_in_res == AtomTypeInRes::O {
                             o_opt = Some(atom.posit);
@@ -76,37 +207,35 @@ impl Protein {
                     );
                     continue;
                 }
-                if c_opt.is_none() {
-                    println!(
-                        "No C atom found for chain {} residue {}",
-                        chain.id, residue_sns
-                    );
-                    continue;
-                }
-                if n_opt.is_none() {
-                    println!(
-                        "No N atom found for chain {} residue {}",
-                        chain.id, residue_sns
-                    );
-                    continue;
-                }
-                if o_opt.is_none() {
-                    println!(
-                        "No O atom found for chain {} residue {}",
-                        chain.id, residue_sns
-                    );
-                    continue;
-                }
 
-                let (ca, c, n, o) = (
-                    ca_opt.unwrap(),
-                    c_opt.unwrap(),
-                    n_opt.unwrap(),
-                    o_opt.unwrap(),
+                // CA-only 和粗粒化模型：缺失的 N/C/O 之后由相邻 CA 估计
+                let ca = ca_opt.unwrap();
+                let absent = [n_opt.is_none(), c_opt.is_none(), o_opt.is_none()];
+                let (c, n, o) = (
+                    c_opt.unwrap_or(ca),
+                    n_opt.unwrap_or(ca),
+                    o_opt.unwrap_or(ca),
                 );
 
                 centers.push(Vec3::new(ca.x as f32, ca.y as f32, ca.z as f32));
 
+                // 保留整个残基的原子（只取第一个构象），供侧链显示和选择使用
+                let residue_atoms = residue
+                    .atom_sns
+                    .iter()
+                    .filter_map(|sn| by_serial.get(sn))
+                    .filter(|a| a.alt_conformation_id.as_deref().is_none_or(|id| id == "A"))
+                    .map(|a| ResidueAtom {
+                        name: a
+                            .type_in_res_general
+                            .clone()
+                            .or_else(|| a.type_in_res.as_ref().map(|t| t.to_string()))
+                            .unwrap_or_default(),
+                        element: a.element,
+                        posit: a.posit,
+                    })
+                    .collect();
+
                 residues.push(Residue {
                     residue_type: amino_acid,
                     ca: ca,
@@ -116,9 +245,17 @@ impl Protein {
                     h: None,
                     sns: residue_sns as usize,
                     ss: None,
+                    b_factor,
+                    approximate_backbone: absent.contains(&true),
+                    atoms: residue_atoms,
+                    auth_seq: Some(residue.res_seq),
+                    ins_code: residue.ins_code.clone(),
+                    auth_chain: residue.auth_chain.clone(),
                 });
+                missing.push(absent);
             }
 
+            approximate_guide_points(&mut residues, &missing);
             chains.push(Chain::new(chain.id.clone(), residues));
         }
 
@@ -128,9 +265,26 @@ impl Protein {
         }
         center = center / (centers.len() as f32);
 
+        // 整个结构只有 CA 时默认画成管子
+        let residues = chains.iter().flat_map(|c| &c.residues);
+        let ca_only =
+            residues.clone().next().is_some() && residues.clone().all(|r| r.approximate_backbone);
+
         Ok(Protein {
             chains: chains,
             center: center,
+            show_hbonds: false,
+            show_sidechains: false,
+            show_gaps: false,
+            sidechain_residues: None,
+            coloring: ProteinColoring::Uniform,
+            representation: if ca_only {
+                ProteinStyle::Tube
+            } else {
+                ProteinStyle::Cartoon
+            },
+            putty_values: None,
+            cartoon: CartoonStyle::default(),
             style: VisualStyle {
                 opacity: 1.0,
                 visible: true,
@@ -140,6 +294,44 @@ impl Protein {
     }
 }
 
+/// Places missing N, C and O atoms from the neighbouring CA atoms, so CA-only traces
+/// still give a spline with plausible guide points.
+fn approximate_guide_points(residues: &mut [Residue], missing: &[[bool; 3]]) {
+    let cas: Vec<Vec3> = residues.iter().map(|r| r.ca).collect();
+    let near = |i: usize, j: usize| cas[i].distance(cas[j]) <= MAX_CA_CA_DISTANCE;
+    for (i, residue) in residues.iter_mut().enumerate() {
+        let [no_n, no_c, no_o] = missing[i];
+        if !(no_n || no_c || no_o) {
+            continue;
+        }
+        let ca = cas[i];
+        let prev = i.checked_sub(1).filter(|&j| near(i, j)).map(|j| cas[j]);
+        let next = cas.get(i + 1).filter(|_| near(i, i + 1)).copied();
+        // 链端用另一侧的 CA 镜像补齐
+        let (prev, next) = match (prev, next) {
+            (Some(p), Some(n)) => (p, n),
+            (Some(p), None) => (p, ca * 2.0 - p),
+            (None, Some(n)) => (ca * 2.0 - n, n),
+            (None, None) => continue,
+        };
+        let forward = (next - ca).normalize_or_zero();
+        let backward = (prev - ca).normalize_or_zero();
+
+        if no_n {
+            residue.n = ca + backward * 1.46;
+        }
+        if no_c {
+            residue.c = ca + forward * 1.52;
+        }
+        if no_o {
+            // 羰基氧垂直于 CA→CA 方向，指向远离相邻残基的一侧
+            let out = -(forward + backward);
+            let perp = (out - forward * out.dot(forward)).normalize_or_zero();
+            residue.o = residue.c + perp * 1.23;
+        }
+    }
+}
+
 impl VisualShape for Protein {
     fn style_mut(&mut self) -> &mut VisualStyle {
         &mut self.style
@@ -151,6 +343,352 @@ impl Protein {
         [self.center.x, self.center.y, self.center.z]
     }
 
+    /// Backbone hydrogen bonds of all chains as `(chain index, bond)` pairs.
+    /// Donor (N-H) and acceptor (C=O) are residue indices within that chain.
+    pub fn get_hbonds(&self) -> Vec<(usize, HydrogenBond)> {
+        self.chains
+            .iter()
+            .enumerate()
+            .flat_map(|(ci, chain)| chain.get_hbonds().iter().map(move |hb| (ci, *hb)))
+            .collect()
+    }
+
+    /// Backbone phi/psi/omega per residue, one `Vec` per chain.
+    pub fn get_dihedrals(&self) -> Vec<Vec<BackboneDihedrals>> {
+        self.chains.iter().map(|c| c.get_dihedrals()).collect()
+    }
+
+    /// Draw backbone hydrogen bonds as dashed sticks between donor N-H and acceptor O.
+    pub fn show_hbonds(mut self, show: bool) -> Self {
+        self.show_hbonds = show;
+        self
+    }
+
+    /// Draw a dashed line across each chain break, e.g. a loop missing from the model.
+    pub fn show_gaps(mut self, show: bool) -> Self {
+        self.show_gaps = show;
+        self
+    }
+
+    /// Draw side chains of all residues as sticks next to the cartoon.
+    pub fn show_sidechains(mut self, show: bool) -> Self {
+        self.show_sidechains = show;
+        self.sidechain_residues = None;
+        self
+    }
+
+    /// Draw side chains only for residues with an atom matching a selection,
+    /// e.g. `within 5 of resi 45`.
+    pub fn sidechains_for(mut self, expr: &str) -> Result<Self, SelectionError> {
+        self.sidechain_residues = Some(self.select_residues(expr)?);
+        self.show_sidechains = true;
+        Ok(self)
+    }
+
+    /// Draw the backbone as cartoon, tube, CA trace or putty.
+    pub fn representation(mut self, representation: ProteinStyle) -> Self {
+        self.representation = representation;
+        self
+    }
+
+    /// Cross-section dimensions and tessellation of the backbone mesh.
+    pub fn cartoon(mut self, cartoon: CartoonStyle) -> Self {
+        self.cartoon = cartoon;
+        self
+    }
+
+    /// Spline samples per residue and vertices per cross-section ring.
+    pub fn cartoon_quality(mut self, pts_per_res: usize, ring_segments: usize) -> Self {
+        self.cartoon.pts_per_res = pts_per_res.max(1);
+        self.cartoon.ring_segments = ring_segments.max(3);
+        self
+    }
+
+    /// Samples per residue and ring segments actually used, reduced for large
+    /// assemblies when [`CartoonStyle::auto_lod`] is set.
+    fn cartoon_detail(&self) -> (usize, usize) {
+        let mut pts = self.cartoon.pts_per_res.max(1);
+        let mut ring = self.cartoon.ring_segments.max(3);
+        if !self.cartoon.auto_lod {
+            return (pts, ring);
+        }
+        let residues: usize = self.chains.iter().map(|c| c.residues.len()).sum();
+        // 先减少环上的顶点，再减少样条采样
+        while residues * (pts + 1) * ring > MAX_CARTOON_VERTICES {
+            if ring > 8 {
+                ring = (ring * 3 / 4).max(8);
+            } else if pts > 2 {
+                pts -= 1;
+            } else {
+                break;
+            }
+        }
+        (pts, ring)
+    }
+
+    /// Putty whose radius follows one value per residue, chain after chain.
+    pub fn putty_values(mut self, values: Vec<f32>) -> Result<Self, ResidueValuesError> {
+        self.check_residue_values(&values)?;
+        self.putty_values = Some(values);
+        self.representation = ProteinStyle::Putty;
+        Ok(self)
+    }
+
+    /// Putty tube radius of every residue of a chain: values are rescaled to
+    /// [`PUTTY_MIN_RADIUS`]..[`PUTTY_MAX_RADIUS`] over the whole protein.
+    fn putty_radii(&self, chain_index: usize) -> Vec<f32> {
+        let values: Vec<Option<f32>> = match &self.putty_values {
+            Some(values) => {
+                let len = self.chains.iter().map(|c| c.residues.len()).sum();
+                (0..len).map(|i| values.get(i).copied()).collect()
+            }
+            None => self
+                .chains
+                .iter()
+                .flat_map(|c| c.residues.iter().map(|r| r.b_factor))
+                .collect(),
+        };
+        let (min, max) = values
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        let range = (max - min).max(1e-6);
+
+        let offset: usize = self.chains[..chain_index]
+            .iter()
+            .map(|c| c.residues.len())
+            .sum();
+        values[offset..offset + self.chains[chain_index].residues.len()]
+            .iter()
+            .map(|v| match v {
+                Some(v) => {
+                    PUTTY_MIN_RADIUS + (v - min) / range * (PUTTY_MAX_RADIUS - PUTTY_MIN_RADIUS)
+                }
+                None => PUTTY_MIN_RADIUS,
+            })
+            .collect()
+    }
+
+    /// Color the cartoon by a [`ProteinColoring`] scheme instead of a single color.
+    pub fn color_by(mut self, coloring: ProteinColoring) -> Self {
+        self.coloring = coloring;
+        self
+    }
+
+    /// Color residues by arbitrary values given chain after chain, e.g. B-factors.
+    pub fn color_by_values(self, values: Vec<f32>) -> Result<Self, ResidueValuesError> {
+        self.check_residue_values(&values)?;
+        Ok(self.color_by(ProteinColoring::Values(values)))
+    }
+
+    /// Values given per residue must match the residue count, otherwise they
+    /// would silently shift onto the wrong residues.
+    fn check_residue_values(&self, values: &[f32]) -> Result<(), ResidueValuesError> {
+        let expected = self.chains.iter().map(|c| c.residues.len()).sum();
+        if values.len() != expected {
+            return Err(ResidueValuesError::CountMismatch {
+                expected,
+                found: values.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Solvent accessible surface area of every residue in Å² (Shrake–Rupley), chain
+    /// after chain as [`ProteinColoring::Values`] expects.
+    pub fn residue_sasa(&self) -> Vec<f32> {
+        let mut owners = Vec::new();
+        let mut posits = Vec::new();
+        let mut radii = Vec::new();
+        let residues = self.chains.iter().flat_map(|c| &c.residues);
+        for (i, residue) in residues.clone().enumerate() {
+            // 旧数据可能没有保存残基原子，退回主链
+            let atoms = if residue.atoms.is_empty() {
+                residue.real_backbone()
+            } else {
+                residue.atoms.iter().map(|a| (a.posit, a.element)).collect()
+            };
+            for (p, e) in atoms {
+                owners.push(i);
+                posits.push(p);
+                radii.push(sasa_radius(&e));
+            }
+        }
+
+        let mut sasa = vec![0.0; residues.count()];
+        let atom_sasa = SasaCalculator::new().compute(&posits, &radii);
+        for (owner, area) in owners.into_iter().zip(atom_sasa) {
+            sasa[owner] += area;
+        }
+        sasa
+    }
+
+    /// Color residues by their solvent accessible surface area.
+    pub fn color_by_sasa(self) -> Self {
+        let sasa = self.residue_sasa();
+        self.color_by(ProteinColoring::Values(sasa))
+    }
+
+    /// Cartoon color of every residue of a chain under the current scheme.
+    fn residue_colors(&self, chain_index: usize) -> Vec<Vec4> {
+        let chain = &self.chains[chain_index];
+        let n = chain.residues.len();
+        let rgb: Vec<Vec3> = match &self.coloring {
+            ProteinColoring::Uniform => {
+                let color = self.style.color.unwrap_or(Vec3::ONE);
+                vec![color; n]
+            }
+            ProteinColoring::Chain => {
+                vec![CHAIN_COLORS[chain_index % CHAIN_COLORS.len()].into(); n]
+            }
+            ProteinColoring::SecondaryStructure => chain
+                .get_ss()
+                .iter()
+                .map(|ss| match ss {
+                    SecondaryStructure::Helix => HELIX_COLOR.into(),
+                    SecondaryStructure::Sheet => SHEET_COLOR.into(),
+                    _ => COIL_COLOR.into(),
+                })
+                .collect(),
+            ProteinColoring::Rainbow => (0..n)
+                .map(|i| rainbow(i as f32 / (n.max(2) - 1) as f32))
+                .collect(),
+            ProteinColoring::ResidueType => chain
+                .residues
+                .iter()
+                .map(|r| match r.residue_type.category() {
+                    AaCategory::Basic => Vec3::new(0.3, 0.45, 1.0),
+                    AaCategory::Acidic => Vec3::new(1.0, 0.3, 0.3),
+                    AaCategory::Polar => Vec3::new(0.35, 0.85, 0.4),
+                    _ => Vec3::new(0.8, 0.8, 0.8),
+                })
+                .collect(),
+            ProteinColoring::Hydrophobicity => chain
+                .residues
+                .iter()
+                .map(|r| blue_white_red((r.residue_type.hydropathicity() + 4.5) / 9.0))
+                .collect(),
+            ProteinColoring::Values(values) => {
+                let (min, max) = values
+                    .iter()
+                    .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+                let range = (max - min).max(1e-6);
+                let offset: usize = self.chains[..chain_index]
+                    .iter()
+                    .map(|c| c.residues.len())
+                    .sum();
+                (offset..offset + n)
+                    .map(|i| match values.get(i) {
+                        Some(v) => blue_white_red((v - min) / range),
+                        None => Vec3::ONE,
+                    })
+                    .collect()
+            }
+        };
+        rgb.into_iter()
+            .map(|c| c.extend(self.style.opacity))
+            .collect()
+    }
+
+    /// CA trace of one chain: a joint at every CA and a stick to the next one,
+    /// each half colored by its residue.
+    fn trace_instances(&self, chain_index: usize, scale: f32) -> InstanceGroups {
+        let mut groups = InstanceGroups::default();
+        let colors = self.residue_colors(chain_index);
+        let chain = &self.chains[chain_index];
+        for segment in chain.get_segments() {
+            for i in segment.clone() {
+                let residue = &chain.residues[i];
+                let sphere = Sphere::new(residue.ca.to_array(), TRACE_RADIUS)
+                    .color_rgba(colors[i].to_array());
+                groups.spheres.push(sphere.to_instance(scale));
+
+                if i + 1 == segment.end {
+                    continue;
+                }
+                let next = &chain.residues[i + 1];
+                let mid = (residue.ca + next.ca) * 0.5;
+                for (end, color) in [(residue.ca, colors[i]), (next.ca, colors[i + 1])] {
+                    let stick = Stick::new(end.to_array(), mid.to_array(), TRACE_RADIUS)
+                        .color_rgba(color.to_array());
+                    groups.sticks.push(stick.to_instance(scale));
+                }
+            }
+        }
+        groups
+    }
+
+    /// Spheres for residues isolated between two chain breaks, which have no spline
+    /// to extrude; sized like the tube or coil profile they stand in for.
+    fn isolated_residue_instances(&self, chain_index: usize, scale: f32) -> InstanceGroups {
+        let mut groups = InstanceGroups::default();
+        let chain = &self.chains[chain_index];
+        let segments: Vec<_> = chain
+            .get_segments()
+            .into_iter()
+            .filter(|s| s.len() == 1)
+            .collect();
+        if segments.is_empty() {
+            return groups;
+        }
+        let colors = self.residue_colors(chain_index);
+        let radii =
+            (self.representation == ProteinStyle::Putty).then(|| self.putty_radii(chain_index));
+        for segment in segments {
+            let i = segment.start;
+            let radius = match self.representation {
+                ProteinStyle::Tube => TUBE_RADIUS,
+                ProteinStyle::Putty => radii.as_ref().map_or(TUBE_RADIUS, |r| r[i]),
+                _ => self.cartoon.coil_radius,
+            };
+            let sphere = Sphere::new(chain.residues[i].ca.to_array(), radius)
+                .color_rgba(colors[i].to_array());
+            groups.spheres.push(sphere.to_instance(scale));
+        }
+        groups
+    }
+
+    /// `(chain, residue)` indices of the residues with an atom matching a selection.
+    pub fn select_residues(&self, expr: &str) -> Result<Vec<(usize, usize)>, SelectionError> {
+        let selection = Selection::parse(expr)?;
+        let mut owners = Vec::new();
+        let mut elements = Vec::new();
+        let mut posits = Vec::new();
+        let mut sites = Vec::new();
+        for (ci, chain) in self.chains.iter().enumerate() {
+            for (ri, residue) in chain.residues.iter().enumerate() {
+                let res_name = residue
+                    .residue_type
+                    .to_str(AaIdent::ThreeLetters)
+                    .to_uppercase();
+                for atom in &residue.atoms {
+                    owners.push((ci, ri));
+                    elements.push(atom.element);
+                    posits.push(atom.posit);
+                    sites.push(AtomSite {
+                        name: atom.name.clone(),
+                        res_name: res_name.clone(),
+                        res_seq: residue.auth_seq.unwrap_or(residue.sns as i32),
+                        ins_code: residue.ins_code.clone(),
+                        chain: residue
+                            .auth_chain
+                            .clone()
+                            .unwrap_or_else(|| chain.id.clone()),
+                        hetero: false,
+                    });
+                }
+            }
+        }
+
+        let mut residues: Vec<(usize, usize)> = selection
+            .evaluate(&elements, &posits, &sites)
+            .into_iter()
+            .map(|i| owners[i])
+            .collect();
+        residues.dedup();
+        Ok(residues)
+    }
+
     pub fn centered(mut self) -> Self {
         let center = Vec3 {
             x: self.center.x,
@@ -163,10 +701,12 @@ impl Protein {
                 residue.c -= center;
                 residue.n -= center;
                 residue.o -= center;
-                residue.o -= center;
                 if let Some(h) = residue.h {
                     residue.h = Some(h - center);
                 }
+                for atom in &mut residue.atoms {
+                    atom.posit -= center;
+                }
             }
         }
         self.center = Vec3::ZERO;
@@ -213,7 +753,11 @@ impl Protein {
     pub fn to_mesh(&self, scale: f32) -> MeshData {
         // use std::time::Instant;
         // let start_total = Instant::now();
-        let pts_per_res = 5;
+        if self.representation == ProteinStyle::Trace {
+            return MeshData::default();
+        }
+        let (pts_per_res, ring_segments) = self.cartoon_detail();
+        let profiles = CartoonSections::new(&self.cartoon, ring_segments);
 
         // println!("to_mesh started");
 
@@ -227,113 +771,115 @@ impl Protein {
 
         // println!("reserve{} {}", estimated_verts, estimated_verts * 2);
 
-        for chain in &self.chains {
+        for (chain_index, chain) in self.chains.iter().enumerate() {
             // let start_chain = Instant::now();
 
-            let mut mesh = MeshData::default();
-
-            // 筛选有效残基
-            let residues: Vec<&Residue> = chain
-                .residues
-                .iter()
-                .filter(|r| r.ca.length_squared() > 1e-6)
-                .collect();
+            // === sections ===
+            // let start_section = Instant::now();
+            let sections: Vec<&RibbonXSection> = match self.representation {
+                ProteinStyle::Tube => vec![&profiles.tube; chain.residues.len()],
+                ProteinStyle::Putty => vec![&profiles.putty; chain.residues.len()],
+                _ => chain
+                    .get_ss()
+                    .iter()
+                    .map(|r| match r {
+                        SecondaryStructure::Helix => &profiles.helix,
+                        SecondaryStructure::Sheet => &profiles.sheet,
+                        _ => &profiles.coil,
+                    })
+                    .collect(),
+            };
+            let radii =
+                (self.representation == ProteinStyle::Putty).then(|| self.putty_radii(chain_index));
+            let colors = self.residue_colors(chain_index);
+            // println!("  section lookup: {:?}", start_section.elapsed());
 
-            let ca_positions: Vec<Vec3> = residues.iter().map(|r| r.ca).collect();
+            // 在断链处拆成独立的片段，各自有样条和端盖
+            for segment in chain.get_segments() {
+                let mut mesh = MeshData::default();
 
-            if ca_positions.len() < 2 {
-                println!("chain {} has less than 2 residues, skipping", chain.id);
-                continue;
-            }
+                let ca_positions: Vec<Vec3> = chain.residues[segment.clone()]
+                    .iter()
+                    .map(|r| r.ca)
+                    .collect();
 
-            // 生成平滑路径
-            let path = self.catmull_rom_chain(&ca_positions, pts_per_res);
-
-            let n = path.len();
-            let mut centers = Vec::with_capacity(n);
-            let mut tangents = Vec::with_capacity(n);
-            let mut normals = Vec::with_capacity(n);
-
-            // === 计算 centers + tangents ===
-            // let start_tangent = Instant::now();
-            for i in 0..n {
-                centers.push(path[i]);
-                let p0 = if i > 0 { path[i - 1] } else { path[0] };
-                let p1 = path[i];
-                let p2 = if i + 1 < n { path[i + 1] } else { path[i] };
-                let p3 = if i + 2 < n { path[i + 2] } else { p2 };
-                tangents.push(catmull_rom_tangent(p0, p1, p2, p3).normalize_or_zero());
-            }
-            // println!("  tangent calculation: {:?}", start_tangent.elapsed());
+                // 单个残基的片段没有样条，由 isolated_residue_instances 画成球
+                if ca_positions.len() < 2 {
+                    continue;
+                }
 
-            // === 初始法线 + Parallel Transport Frame ===
-            // let start_normal = Instant::now();
-            fn initial_normal(t: Vec3) -> Vec3 {
-                if t.dot(Vec3::Z).abs() < 0.98 {
-                    t.cross(Vec3::Z).normalize()
-                } else {
-                    t.cross(Vec3::X).normalize()
+                // 生成平滑路径
+                let path = self.catmull_rom_chain(&ca_positions, pts_per_res);
+
+                let n = path.len();
+                let mut centers = Vec::with_capacity(n);
+                let mut tangents = Vec::with_capacity(n);
+                let mut normals = Vec::with_capacity(n);
+
+                // === 计算 centers + tangents ===
+                // let start_tangent = Instant::now();
+                for i in 0..n {
+                    centers.push(path[i]);
+                    let p0 = if i > 0 { path[i - 1] } else { path[0] };
+                    let p1 = path[i];
+                    let p2 = if i + 1 < n { path[i + 1] } else { path[i] };
+                    let p3 = if i + 2 < n { path[i + 2] } else { p2 };
+                    tangents.push(catmull_rom_tangent(p0, p1, p2, p3).normalize_or_zero());
+                }
+                // println!("  tangent calculation: {:?}", start_tangent.elapsed());
+
+                // === 初始法线 + Parallel Transport Frame ===
+                // let start_normal = Instant::now();
+                fn initial_normal(t: Vec3) -> Vec3 {
+                    if t.dot(Vec3::Z).abs() < 0.98 {
+                        t.cross(Vec3::Z).normalize()
+                    } else {
+                        t.cross(Vec3::X).normalize()
+                    }
                 }
-            }
 
-            let mut current_normal = initial_normal(tangents[0]);
-            normals.push(current_normal);
+                let mut current_normal = initial_normal(tangents[0]);
+                normals.push(current_normal);
 
-            for i in 1..centers.len() {
-                let prev_t = tangents[i - 1];
-                let curr_t = tangents[i];
+                for i in 1..centers.len() {
+                    let prev_t = tangents[i - 1];
+                    let curr_t = tangents[i];
 
-                let rotation_axis = prev_t.cross(curr_t);
-                if rotation_axis.length_squared() > 1e-6 {
-                    let rotation_angle = prev_t.angle_between(curr_t);
-                    let rotation = Quat::from_axis_angle(rotation_axis.normalize(), rotation_angle);
-                    current_normal = rotation * current_normal;
+                    let rotation_axis = prev_t.cross(curr_t);
+                    if rotation_axis.length_squared() > 1e-6 {
+                        let rotation_angle = prev_t.angle_between(curr_t);
+                        let rotation =
+                            Quat::from_axis_angle(rotation_axis.normalize(), rotation_angle);
+                        current_normal = rotation * current_normal;
+                    }
+                    normals.push(current_normal);
                 }
-                normals.push(current_normal);
-            }
-
-            // println!("  normal calculation: {:?}", start_normal.elapsed());
 
-            // === sections ===
-            // let start_section = Instant::now();
-            let sections: Vec<&RibbonXSection> = chain
-                .get_ss()
-                .iter()
-                .map(|r| match r {
-                    SecondaryStructure::Helix => &*HELIX_SECTION,
-                    SecondaryStructure::Sheet => &*SHEET_SECTION,
-                    _ => &*COIL_SECTION,
-                })
-                .collect();
-            // println!("  section lookup: {:?}", start_section.elapsed());
+                // println!("  normal calculation: {:?}", start_normal.elapsed());
+
+                // === extrusion ===
+                // let start_extrude = Instant::now();
+                self.extrude_ribbon_corrected(
+                    &centers,
+                    &tangents,
+                    &normals,
+                    &sections[segment.clone()],
+                    &colors[segment.clone()],
+                    radii.as_ref().map(|r| &r[segment.clone()]),
+                    pts_per_res,
+                    &mut mesh,
+                );
+                // println!("  extrusion: {:?}", start_extrude.elapsed());
 
-            // === extrusion ===
-            // let start_extrude = Instant::now();
-            self.extrude_ribbon_corrected(
-                &centers,
-                &tangents,
-                &normals,
-                &sections,
-                pts_per_res,
-                &mut mesh,
-            );
-            // println!("  extrusion: {:?}", start_extrude.elapsed());
+                // === scale ===
+                // let start_post = Instant::now();
+                for v in &mut mesh.vertices {
+                    *v *= scale;
+                }
+                // println!("  postprocess: {:?}", start_post.elapsed());
 
-            // === scale + colors ===
-            // let start_post = Instant::now();
-            for v in &mut mesh.vertices {
-                *v *= scale;
+                final_mesh.append(&mesh);
             }
-
-            let color = match self.style.color {
-                Some(color) => Vec4::new(color[0], color[1], color[2], self.style.opacity),
-                None => Vec4::new(1.0, 1.0, 1.0, 1.0),
-            };
-
-            mesh.colors = Some(vec![color; mesh.vertices.len()]);
-            // println!("  postprocess: {:?}", start_post.elapsed());
-
-            final_mesh.append(&mesh);
             // println!(
             //     "chain {} processed in {:?}",
             //     chain.id,
@@ -352,27 +898,29 @@ impl Protein {
     }
 
     // 完全修正版的 extrusion（不再有任何越界、箭头方向、端盖问题）
+    #[allow(clippy::too_many_arguments)]
     fn extrude_ribbon_corrected(
         &self,
         centers: &[Vec3],
         tangents: &[Vec3],
         normals: &[Vec3],
         sections: &[&RibbonXSection],
+        residue_colors: &[Vec4],
+        residue_radii: Option<&[f32]>,
         pts_per_res: usize,
         mesh: &mut MeshData,
     ) {
         let base_v = mesh.vertices.len() as u32;
+        let mut colors = mesh.colors.take().unwrap_or_default();
 
-        for (seg, xs) in sections.iter().enumerate() {
+        // 第 seg 段从残基 seg 到 seg + 1，最后一个残基只是终点
+        let n_segments = sections.len().saturating_sub(1);
+        for (seg, xs) in sections.iter().take(n_segments).enumerate() {
             let start = seg * pts_per_res;
-            let end = if seg + 1 < sections.len() {
-                (seg + 1) * pts_per_res + 1
-            } else {
-                centers.len()
-            };
+            let end = (seg + 1) * pts_per_res + 1;
 
             let cap_front = seg == 0;
-            let cap_back = seg + 1 == sections.len();
+            let cap_back = seg + 1 == n_segments;
 
             let (coords, arrow_back) = if xs.arrow_coords.is_some() {
                 (&xs.coords[..], xs.arrow_coords.as_deref())
@@ -380,6 +928,7 @@ impl Protein {
                 (&xs.coords[..], None)
             };
 
+            let seg_base = mesh.vertices.len();
             self.extrude_one_segment_simd(
                 &centers[start..end],
                 &tangents[start..end],
@@ -390,8 +939,26 @@ impl Protein {
                 cap_back,
                 mesh,
                 xs.ss,
+                xs.half_axes,
             );
+
+            // 每个环上的顶点按样条位置在相邻残基的颜色（和 putty 半径）之间插值
+            let from = residue_colors.get(seg).copied().unwrap_or(Vec4::ONE);
+            let to = residue_colors.get(seg + 1).copied().unwrap_or(from);
+            for v in 0..mesh.vertices.len() - seg_base {
+                let k = v / coords.len();
+                let t = (k as f32 / pts_per_res as f32).min(1.0);
+                colors.push(from.lerp(to, t));
+                if let Some(radii) = residue_radii {
+                    let r0 = radii.get(seg).copied().unwrap_or(1.0);
+                    let r1 = radii.get(seg + 1).copied().unwrap_or(r0);
+                    let center = centers[start + k];
+                    let vertex = &mut mesh.vertices[seg_base + v];
+                    *vertex = center + (*vertex - center) * (r0 + (r1 - r0) * t);
+                }
+            }
         }
+        mesh.colors = Some(colors);
 
         // 统一偏移索引
         for idx in &mut mesh.indices {
@@ -411,6 +978,7 @@ impl Protein {
         cap_back: bool,
         mesh: &mut MeshData,
         ss: SecondaryStructure,
+        half_axes: [f32; 2],
     ) {
         let n_ring = coords.len();
         let n_pts = centers.len();
@@ -461,6 +1029,9 @@ impl Protein {
             };
             let local_t = f32x8::new(local_t_arr);
 
+            // 先按环收集，再按点写入：索引假定顶点按点排列（每点 n_ring 个）
+            let mut block = vec![(Vec3::ZERO, Vec3::ZERO); 8 * n_ring];
+
             // has_arrow: 模拟 horizontal_max (用 to_array + 标量 max)
             let has_arrow = if let Some(arrow_back) = arrow_back {
                 let local_t_arr = local_t.to_array(); // 修复：用 to_array() 提取
@@ -503,8 +1074,8 @@ impl Protein {
                             (nx, ny, nz),
                             (bx, by, bz),
                             (off_n, off_b),
-                            1.0,  // width
-                            0.25, // height
+                            half_axes[0], // width
+                            half_axes[1], // height
                         )
                     }
                     SecondaryStructure::Sheet => match ring_idx {
@@ -534,15 +1105,15 @@ impl Protein {
                 let nz_arr = nz_out.to_array();
 
                 for j in 0..8 {
-                    if i + j >= n_pts {
-                        break;
-                    }
                     let pos = Vec3::new(px_arr[j], py_arr[j], pz_arr[j]); // 修复：数组索引
                     let nor = Vec3::new(nx_arr[j], ny_arr[j], nz_arr[j]).normalize_or_zero();
-                    mesh.vertices.push(pos);
-                    mesh.normals.push(nor);
+                    block[j * n_ring + ring_idx] = (pos, nor);
                 }
             }
+            for (pos, nor) in block {
+                mesh.vertices.push(pos);
+                mesh.normals.push(nor);
+            }
             i += 8;
         }
 
@@ -568,7 +1139,9 @@ impl Protein {
                 }
                 let pos = c + n * off[0] + b * off[1];
                 let nor = match ss {
-                    SecondaryStructure::Helix => ellipse_normal(n, b, off, 1.0, 0.25),
+                    SecondaryStructure::Helix => {
+                        ellipse_normal(n, b, off, half_axes[0], half_axes[1])
+                    }
                     SecondaryStructure::Sheet => match ring_idx {
                         0 | 1 => b,
                         2 | 3 => -n,
@@ -643,6 +1216,22 @@ impl Protein {
     }
 }
 
+/// Amide hydrogen of `residue`: the `H` atom of the file when present, otherwise
+/// placed as in DSSP, opposite the C=O of the preceding residue.
+fn amide_hydrogen(residue: &Residue, prev: Option<&Residue>) -> Option<Vec3> {
+    if let Some(h) = residue.h {
+        return Some(h);
+    }
+    if let Some(atom) = residue.atoms.iter().find(|a| a.name == "H") {
+        return Some(atom.posit);
+    }
+    let prev = prev.filter(|p| {
+        !p.approximate_backbone && p.c.distance(residue.n) <= MAX_PEPTIDE_BOND_LENGTH
+    })?;
+    let direction = (prev.c - prev.o).normalize_or_zero();
+    (direction != Vec3::ZERO).then(|| residue.n + direction * AMIDE_NH_LENGTH)
+}
+
 fn ellipse_normal(n: Vec3, b: Vec3, off: [f32; 2], width: f32, height: f32) -> Vec3 {
     let x = off[0];
     let y = off[1];
@@ -676,41 +1265,210 @@ fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
     p0 * c0 + p1 * c1 + p2 * c2 + p3 * c3
 }
 
+impl IntoInstanceGroups for Protein {
+    fn to_instance_group(&self, scale: f32) -> InstanceGroups {
+        let mut groups = InstanceGroups::default();
+
+        for ci in 0..self.chains.len() {
+            if self.representation == ProteinStyle::Trace {
+                groups.merge(self.trace_instances(ci, scale));
+            } else {
+                groups.merge(self.isolated_residue_instances(ci, scale));
+            }
+        }
+
+        if self.show_sidechains {
+            let selected: Option<HashSet<(usize, usize)>> = self
+                .sidechain_residues
+                .as_ref()
+                .map(|r| r.iter().copied().collect());
+            for (ci, chain) in self.chains.iter().enumerate() {
+                for (ri, residue) in chain.residues.iter().enumerate() {
+                    if selected.as_ref().is_none_or(|s| s.contains(&(ci, ri))) {
+                        groups.merge(sidechain_instances(residue, self.style.opacity, scale));
+                    }
+                }
+            }
+        }
+
+        if self.show_gaps {
+            for chain in &self.chains {
+                let segments = chain.get_segments();
+                for pair in segments.windows(2) {
+                    let before = chain.residues[pair[0].end - 1].ca;
+                    let after = chain.residues[pair[1].start].ca;
+                    let stick = Stick::new(before.to_array(), after.to_array(), GAP_RADIUS)
+                        .color(GAP_COLOR)
+                        .opacity(self.style.opacity);
+                    groups
+                        .sticks
+                        .extend(stick.to_dashed_instances(scale, GAP_DASH, GAP_GAP));
+                }
+            }
+        }
+
+        if !self.show_hbonds {
+            return groups;
+        }
+
+        for chain in &self.chains {
+            for hb in chain.get_hbonds() {
+                let donor = &chain.residues[hb.donor_idx];
+                let acceptor = &chain.residues[hb.acceptor_idx];
+                let prev = hb.donor_idx.checked_sub(1).map(|i| &chain.residues[i]);
+                let start = amide_hydrogen(donor, prev).unwrap_or(donor.n);
+
+                let stick = Stick::new(start.to_array(), acceptor.o.to_array(), HBOND_RADIUS)
+                    .color(HBOND_COLOR)
+                    .opacity(self.style.opacity);
+                groups
+                    .sticks
+                    .extend(stick.to_dashed_instances(scale, HBOND_DASH, HBOND_GAP));
+            }
+        }
+        groups
+    }
+}
+
+impl Interpolatable for Protein {
+    /// Backbone and side-chain atoms move linearly per residue; the secondary
+    /// structure of the nearer frame is held so the cartoon does not flicker.
+    fn interpolate(&self, other: &Self, t: f32, logger: impl Logger) -> Self {
+        let same_shape = self.chains.len() == other.chains.len()
+            && self
+                .chains
+                .iter()
+                .zip(&other.chains)
+                .all(|(a, b)| a.residues.len() == b.residues.len());
+        if !same_shape {
+            logger.warn(
+                "Protein interpolation skipped: chain or residue counts differ between frames",
+            );
+            return self.clone();
+        }
+
+        let chains = self
+            .chains
+            .iter()
+            .zip(&other.chains)
+            .map(|(a, b)| {
+                let held = if t < 0.5 { a.get_ss() } else { b.get_ss() };
+                let residues = a
+                    .residues
+                    .iter()
+                    .zip(&b.residues)
+                    .zip(held)
+                    .map(|((ra, rb), ss)| {
+                        // 原子数不同（例如缺失侧链）时保持当前帧的侧链
+                        let atoms = if ra.atoms.len() == rb.atoms.len() {
+                            ra.atoms
+                                .iter()
+                                .zip(&rb.atoms)
+                                .map(|(x, y)| ResidueAtom {
+                                    posit: x.posit.lerp(y.posit, t),
+                                    ..x.clone()
+                                })
+                                .collect()
+                        } else {
+                            ra.atoms.clone()
+                        };
+                        Residue {
+                            ca: ra.ca.lerp(rb.ca, t),
+                            c: ra.c.lerp(rb.c, t),
+                            n: ra.n.lerp(rb.n, t),
+                            o: ra.o.lerp(rb.o, t),
+                            h: match (ra.h, rb.h) {
+                                (Some(x), Some(y)) => Some(x.lerp(y, t)),
+                                _ => ra.h,
+                            },
+                            ss: Some(*ss),
+                            b_factor: match (ra.b_factor, rb.b_factor) {
+                                (Some(x), Some(y)) => Some(x + (y - x) * t),
+                                _ => ra.b_factor,
+                            },
+                            atoms,
+                            ..ra.clone()
+                        }
+                    })
+                    .collect();
+                Chain::new(a.id.clone(), residues)
+            })
+            .collect();
+
+        let lerp_values = |a: &Vec<f32>, b: &Vec<f32>| -> Vec<f32> {
+            if a.len() == b.len() {
+                a.iter().zip(b).map(|(x, y)| x + (y - x) * t).collect()
+            } else {
+                a.clone()
+            }
+        };
+        let coloring = match (&self.coloring, &other.coloring) {
+            (ProteinColoring::Values(a), ProteinColoring::Values(b)) => {
+                ProteinColoring::Values(lerp_values(a, b))
+            }
+            _ => self.coloring.clone(),
+        };
+        let putty_values = match (&self.putty_values, &other.putty_values) {
+            (Some(a), Some(b)) => Some(lerp_values(a, b)),
+            _ => self.putty_values.clone(),
+        };
+
+        Self {
+            chains,
+            center: self.center.lerp(other.center, t),
+            coloring,
+            putty_values,
+            ..self.clone()
+        }
+    }
+}
+
 impl Into<Shape> for Protein {
     fn into(self) -> Shape {
         Shape::Protein(self)
     }
 }
 
-use once_cell::sync::Lazy;
-
-// Helix
-static HELIX_SECTION: Lazy<RibbonXSection> =
-    Lazy::new(|| RibbonXSection::smooth_circle().scale(1.0, 0.25).as_helix());
-
-// Sheet
-static SHEET_SECTION: Lazy<RibbonXSection> = Lazy::new(|| {
-    // Arrow: 前半段宽 1.0 → 0.0，后半段保持 0.0
-    RibbonXSection::smooth_circle()
-        .scale(1.0, 0.25)
-        .arrow(1.0, 1.0, 0.0, 1.2)
-});
+/// Cross-section profiles of one mesh, tessellated for the current quality.
+struct CartoonSections {
+    helix: RibbonXSection,
+    sheet: RibbonXSection,
+    coil: RibbonXSection,
+    tube: RibbonXSection,
+    // Putty：单位圆，半径在 extrusion 时按残基缩放
+    putty: RibbonXSection,
+}
 
-// Coil
-static COIL_SECTION: Lazy<RibbonXSection> =
-    Lazy::new(|| RibbonXSection::smooth_circle().scale(0.2, 0.2));
+impl CartoonSections {
+    fn new(cartoon: &CartoonStyle, ring_segments: usize) -> Self {
+        let circle = || RibbonXSection::smooth_circle(ring_segments);
+        Self {
+            helix: circle()
+                .scale(cartoon.helix_width * 0.5, cartoon.helix_thickness * 0.5)
+                .as_helix(),
+            sheet: RibbonXSection::rectangle(
+                cartoon.sheet_thickness * 0.5,
+                cartoon.sheet_width * 0.5,
+            ),
+            coil: circle().scale(cartoon.coil_radius, cartoon.coil_radius),
+            tube: circle().scale(TUBE_RADIUS, TUBE_RADIUS),
+            putty: circle(),
+        }
+    }
+}
 
 struct RibbonXSection {
     coords: Vec<[f32; 2]>,               // 基础 2D 轮廓
     arrow_coords: Option<Vec<[f32; 2]>>, // 为 Sheet 箭头准备的第二套轮廓
     ss: SecondaryStructure,
+    /// Semi-axes of the elliptical profile along N and B, for helix normals.
+    half_axes: [f32; 2],
     _smooth: bool,
 }
 
 impl RibbonXSection {
-    fn smooth_circle() -> Self {
+    fn smooth_circle(n: usize) -> Self {
         let mut coords = Vec::new();
-        let n = 32;
         for i in 0..n {
             let a = (i as f32) / (n as f32) * std::f32::consts::TAU;
             coords.push([a.cos(), a.sin()]);
@@ -719,6 +1477,7 @@ impl RibbonXSection {
             coords: coords.to_vec(),
             arrow_coords: None,
             ss: SecondaryStructure::Coil,
+            half_axes: [1.0, 1.0],
             _smooth: true,
         }
     }
@@ -734,34 +1493,27 @@ impl RibbonXSection {
                 c[1] *= sy;
             }
         }
+        self.half_axes = [self.half_axes[0] * sx, self.half_axes[1] * sy];
         self
     }
 
-    fn arrow(mut self, sx1: f32, sy1: f32, sx2: f32, sy2: f32) -> Self {
-        let mut back = self.coords.clone();
-        for c in &mut self.coords {
-            c[0] *= sx1;
-            c[1] *= sy1;
-        }
-        for c in &mut back {
-            c[0] *= sx2;
-            c[1] *= sy2;
-        }
-        self.arrow_coords = Some(back);
+    /// Flat sheet profile; every corner appears twice so each face gets its own normal.
+    fn rectangle(half_n: f32, half_b: f32) -> Self {
         Self {
             coords: [
-                [0.2, 1.0],
-                [-0.2, 1.0],
-                [-0.2, 1.0],
-                [-0.2, -1.0],
-                [-0.2, -1.0],
-                [0.2, -1.0],
-                [0.2, -1.0],
-                [0.2, 1.0],
+                [half_n, half_b],
+                [-half_n, half_b],
+                [-half_n, half_b],
+                [-half_n, -half_b],
+                [-half_n, -half_b],
+                [half_n, -half_b],
+                [half_n, -half_b],
+                [half_n, half_b],
             ]
             .into(),
             arrow_coords: None,
             ss: SecondaryStructure::Sheet,
+            half_axes: [half_n, half_b],
             _smooth: true,
         }
     }
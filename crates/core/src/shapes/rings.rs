@@ -0,0 +1,273 @@
+// 最小环集合（SSSR）：Horton 候选环——每个原子到每条环上的键（非桥）两端的最短路径
+// 加上这条键，按大小排序后用 GF(2) 上的线性无关性挑选，直到环数等于圈秩 E - V + C。
+// 候选集一定包含一组最小环基，立方烷这类笼状分子也能找全。
+//
+// Horton, SIAM J. Comput. 16:358-366 (1987) https://doi.org/10.1137/0216026
+// https://doi.org/10.1021/ci00004a010 (Downs et al., review of ring perception)
+
+use std::collections::{HashSet, VecDeque};
+
+/// Smallest set of smallest rings, each as atom indices in ring order. Rings larger
+/// than `max_size` atoms are left out when a limit is given.
+pub(crate) fn sssr(
+    n_atoms: usize,
+    bonds: &[[usize; 2]],
+    max_size: Option<usize>,
+) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![Vec::new(); n_atoms];
+    for (e, &[a, b]) in bonds.iter().enumerate() {
+        if a != b && a < n_atoms && b < n_atoms {
+            adjacency[a].push((b, e));
+            adjacency[b].push((a, e));
+        }
+    }
+
+    // 桥不在任何环上，只保留环上的键；去掉桥不改变圈秩
+    let bridges = bridges(&adjacency, bonds.len());
+    for neighbours in &mut adjacency {
+        neighbours.retain(|(_, e)| !bridges[*e]);
+    }
+    let edges = adjacency.iter().map(Vec::len).sum::<usize>() / 2;
+    let rank = (edges + connected_components(&adjacency)).saturating_sub(n_atoms);
+    if rank == 0 {
+        return Vec::new();
+    }
+
+    // 环上的键重新编号，边向量只需覆盖这些键
+    let mut column = vec![usize::MAX; bonds.len()];
+    let mut ring_bonds = Vec::new();
+    for neighbours in &adjacency {
+        for &(_, e) in neighbours {
+            if column[e] == usize::MAX {
+                column[e] = ring_bonds.len();
+                ring_bonds.push(e);
+            }
+        }
+    }
+
+    // 候选环：以每个环上原子为根的最短路径树，加一条非树边闭合
+    let mut tree = PathTree::new(n_atoms);
+    let max_len = max_size.unwrap_or(usize::MAX);
+    let mut candidates = Vec::new();
+    for root in 0..n_atoms {
+        if !adjacency[root].is_empty() {
+            tree.grow(&adjacency, root, max_len);
+            tree.close_cycles(&adjacency, max_len, &mut candidates);
+        }
+    }
+    candidates.sort_by_key(|ring| ring.len());
+
+    let edge_of = |u: usize, v: usize| adjacency[u].iter().find(|(w, _)| *w == v).map(|(_, e)| *e);
+
+    // 高斯消元维护已选环的边向量（位集），新环与之线性无关才收下
+    let words = ring_bonds.len().div_ceil(64);
+    let mut basis: Vec<Vec<u64>> = Vec::new();
+    let mut rings = Vec::new();
+    let mut seen = HashSet::new();
+    for ring in candidates {
+        let mut vector = vec![0u64; words];
+        for k in 0..ring.len() {
+            if let Some(e) = edge_of(ring[k], ring[(k + 1) % ring.len()]) {
+                let c = column[e];
+                vector[c / 64] ^= 1 << (c % 64);
+            }
+        }
+        if !seen.insert(vector.clone()) {
+            continue;
+        }
+        let mut reduced = vector;
+        for row in &basis {
+            let pivot = leading_bit(row);
+            if reduced[pivot / 64] & (1 << (pivot % 64)) != 0 {
+                for (r, b) in reduced.iter_mut().zip(row) {
+                    *r ^= b;
+                }
+            }
+        }
+        if reduced.iter().all(|w| *w == 0) {
+            continue;
+        }
+        // 保持基按主元排序，消元时才能一遍完成
+        let pivot = leading_bit(&reduced);
+        let at = basis
+            .iter()
+            .position(|row| leading_bit(row) > pivot)
+            .unwrap_or(basis.len());
+        basis.insert(at, reduced);
+        rings.push(ring);
+        if rings.len() == rank {
+            break;
+        }
+    }
+    rings
+}
+
+/// Bonds whose removal disconnects the graph, found with Tarjan's low-link values.
+fn bridges(adjacency: &[Vec<(usize, usize)>], n_bonds: usize) -> Vec<bool> {
+    let n = adjacency.len();
+    let mut bridge = vec![false; n_bonds];
+    let mut order = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut counter = 0;
+    for root in 0..n {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        order[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        // (原子, 进入它的键, 下一个要看的邻居)；按键而不是原子跳过父节点，重复的键也算环
+        let mut stack = vec![(root, usize::MAX, 0)];
+        while let Some(top) = stack.last_mut() {
+            let (u, via) = (top.0, top.1);
+            if let Some(&(v, e)) = adjacency[u].get(top.2) {
+                top.2 += 1;
+                if e == via {
+                    continue;
+                }
+                if order[v] == usize::MAX {
+                    order[v] = counter;
+                    low[v] = counter;
+                    counter += 1;
+                    stack.push((v, e, 0));
+                } else {
+                    low[u] = low[u].min(order[v]);
+                }
+            } else {
+                stack.pop();
+                if let Some(&(p, _, _)) = stack.last() {
+                    low[p] = low[p].min(low[u]);
+                    if low[u] > order[p] {
+                        bridge[via] = true;
+                    }
+                }
+            }
+        }
+    }
+    bridge
+}
+
+fn leading_bit(row: &[u64]) -> usize {
+    row.iter()
+        .enumerate()
+        .find(|(_, w)| **w != 0)
+        .map(|(i, w)| i * 64 + w.trailing_zeros() as usize)
+        .unwrap_or(usize::MAX)
+}
+
+fn connected_components(adjacency: &[Vec<(usize, usize)>]) -> usize {
+    let mut visited = vec![false; adjacency.len()];
+    let mut components = 0;
+    for start in 0..adjacency.len() {
+        if visited[start] {
+            continue;
+        }
+        components += 1;
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(u) = stack.pop() {
+            for &(v, _) in &adjacency[u] {
+                if !visited[v] {
+                    visited[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Shortest-path tree of one root, reused across roots; only visited atoms are reset.
+struct PathTree {
+    root: usize,
+    parent: Vec<usize>,
+    parent_bond: Vec<usize>,
+    depth: Vec<usize>,
+    /// Child of the root that each atom descends from.
+    branch: Vec<usize>,
+    visited: Vec<usize>,
+}
+
+impl PathTree {
+    fn new(n_atoms: usize) -> Self {
+        Self {
+            root: 0,
+            parent: vec![usize::MAX; n_atoms],
+            parent_bond: vec![usize::MAX; n_atoms],
+            depth: vec![0; n_atoms],
+            branch: vec![usize::MAX; n_atoms],
+            visited: Vec::new(),
+        }
+    }
+
+    /// Breadth-first tree from `root`, deep enough for rings of `max_len` atoms.
+    fn grow(&mut self, adjacency: &[Vec<(usize, usize)>], root: usize, max_len: usize) {
+        for &v in &self.visited {
+            self.parent[v] = usize::MAX;
+        }
+        self.visited.clear();
+
+        self.root = root;
+        self.parent[root] = root;
+        self.parent_bond[root] = usize::MAX;
+        self.depth[root] = 0;
+        self.branch[root] = root;
+        self.visited.push(root);
+        let mut queue = VecDeque::from([root]);
+        while let Some(u) = queue.pop_front() {
+            // 环上的原子数最多是两端深度之和加一
+            if 2 * self.depth[u] + 1 > max_len {
+                continue;
+            }
+            for &(v, e) in &adjacency[u] {
+                if self.parent[v] == usize::MAX {
+                    self.parent[v] = u;
+                    self.parent_bond[v] = e;
+                    self.depth[v] = self.depth[u] + 1;
+                    self.branch[v] = if u == root { v } else { self.branch[u] };
+                    self.visited.push(v);
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+
+    /// Rings made of the tree paths to both ends of a non-tree bond, when the two
+    /// paths only meet at the root.
+    fn close_cycles(
+        &self,
+        adjacency: &[Vec<(usize, usize)>],
+        max_len: usize,
+        rings: &mut Vec<Vec<usize>>,
+    ) {
+        for &x in &self.visited {
+            for &(y, e) in &adjacency[x] {
+                if x > y
+                    || self.parent[y] == usize::MAX
+                    || self.parent_bond[x] == e
+                    || self.parent_bond[y] == e
+                    || self.branch[x] == self.branch[y]
+                    || self.depth[x] + self.depth[y] + 1 > max_len
+                {
+                    continue;
+                }
+                // 根 -> x，再 y -> 根（不含根）
+                let mut ring = self.path_to_root(x);
+                ring.reverse();
+                let mut back = self.path_to_root(y);
+                back.pop();
+                ring.extend(back);
+                rings.push(ring);
+            }
+        }
+    }
+
+    fn path_to_root(&self, mut v: usize) -> Vec<usize> {
+        let mut path = vec![v];
+        while v != self.root {
+            v = self.parent[v];
+            path.push(v);
+        }
+        path
+    }
+}
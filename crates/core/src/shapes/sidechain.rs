@@ -0,0 +1,237 @@
+// 标准氨基酸侧链的成键模板（重原子），氢按距离连到最近的重原子。
+//
+// https://www.wwpdb.org/data/ccd
+
+use glam::Vec3;
+use na_seq::{AaIdent, Element};
+
+use crate::parser::utils::{Residue, ResidueAtom};
+use crate::shapes::molecules::my_color;
+use crate::shapes::{Sphere, Stick};
+use crate::utils::{InstanceGroups, VisualShape};
+
+/// Radius in Å of side-chain sticks and their joints.
+const SIDECHAIN_RADIUS: f32 = 0.15;
+
+/// Side-chain bonds by residue name, from CA outwards. Proline closes its ring on N.
+const TEMPLATES: &[(&str, &[(&str, &str)])] = &[
+    ("ALA", &[("CA", "CB")]),
+    (
+        "ARG",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD"),
+            ("CD", "NE"),
+            ("NE", "CZ"),
+            ("CZ", "NH1"),
+            ("CZ", "NH2"),
+        ],
+    ),
+    (
+        "ASN",
+        &[("CA", "CB"), ("CB", "CG"), ("CG", "OD1"), ("CG", "ND2")],
+    ),
+    (
+        "ASP",
+        &[("CA", "CB"), ("CB", "CG"), ("CG", "OD1"), ("CG", "OD2")],
+    ),
+    ("CYS", &[("CA", "CB"), ("CB", "SG")]),
+    (
+        "GLN",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD"),
+            ("CD", "OE1"),
+            ("CD", "NE2"),
+        ],
+    ),
+    (
+        "GLU",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD"),
+            ("CD", "OE1"),
+            ("CD", "OE2"),
+        ],
+    ),
+    ("GLY", &[]),
+    (
+        "HIS",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "ND1"),
+            ("ND1", "CE1"),
+            ("CE1", "NE2"),
+            ("NE2", "CD2"),
+            ("CD2", "CG"),
+        ],
+    ),
+    (
+        "ILE",
+        &[("CA", "CB"), ("CB", "CG1"), ("CB", "CG2"), ("CG1", "CD1")],
+    ),
+    (
+        "LEU",
+        &[("CA", "CB"), ("CB", "CG"), ("CG", "CD1"), ("CG", "CD2")],
+    ),
+    (
+        "LYS",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD"),
+            ("CD", "CE"),
+            ("CE", "NZ"),
+        ],
+    ),
+    (
+        "MET",
+        &[("CA", "CB"), ("CB", "CG"), ("CG", "SD"), ("SD", "CE")],
+    ),
+    (
+        "PHE",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD1"),
+            ("CD1", "CE1"),
+            ("CE1", "CZ"),
+            ("CZ", "CE2"),
+            ("CE2", "CD2"),
+            ("CD2", "CG"),
+        ],
+    ),
+    (
+        "PRO",
+        &[("CA", "CB"), ("CB", "CG"), ("CG", "CD"), ("CD", "N")],
+    ),
+    ("SER", &[("CA", "CB"), ("CB", "OG")]),
+    ("THR", &[("CA", "CB"), ("CB", "OG1"), ("CB", "CG2")]),
+    (
+        "TRP",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD1"),
+            ("CD1", "NE1"),
+            ("NE1", "CE2"),
+            ("CE2", "CD2"),
+            ("CD2", "CG"),
+            ("CE2", "CZ2"),
+            ("CZ2", "CH2"),
+            ("CH2", "CZ3"),
+            ("CZ3", "CE3"),
+            ("CE3", "CD2"),
+        ],
+    ),
+    (
+        "TYR",
+        &[
+            ("CA", "CB"),
+            ("CB", "CG"),
+            ("CG", "CD1"),
+            ("CD1", "CE1"),
+            ("CE1", "CZ"),
+            ("CZ", "CE2"),
+            ("CE2", "CD2"),
+            ("CD2", "CG"),
+            ("CZ", "OH"),
+        ],
+    ),
+    ("VAL", &[("CA", "CB"), ("CB", "CG1"), ("CB", "CG2")]),
+    ("SEC", &[("CA", "CB"), ("CB", "SE")]),
+];
+
+const BACKBONE: [&str; 4] = ["N", "C", "O", "OXT"];
+
+/// Bonds of a residue's side chain as index pairs into `residue.atoms`.
+///
+/// Residues without a template fall back to distance-based bonding.
+pub(crate) fn sidechain_bonds(residue: &Residue) -> Vec<[usize; 2]> {
+    let atoms = &residue.atoms;
+    let find = |name: &str| atoms.iter().position(|a| a.name == name);
+    let is_hydrogen = |a: &ResidueAtom| a.element == Element::Hydrogen;
+
+    let name = residue
+        .residue_type
+        .to_str(AaIdent::ThreeLetters)
+        .to_uppercase();
+    let mut bonds: Vec<[usize; 2]> = match TEMPLATES.iter().find(|(n, _)| *n == name) {
+        Some((_, template)) => template
+            .iter()
+            .filter_map(|(a, b)| Some([find(a)?, find(b)?]))
+            .collect(),
+        None => {
+            // 非标准残基：侧链重原子之间按距离成键
+            let side: Vec<usize> = (0..atoms.len())
+                .filter(|&i| !is_hydrogen(&atoms[i]) && !BACKBONE.contains(&atoms[i].name.as_str()))
+                .collect();
+            let mut bonds = Vec::new();
+            for (k, &i) in side.iter().enumerate() {
+                for &j in &side[k + 1..] {
+                    if atoms[i].posit.distance_squared(atoms[j].posit) < 1.9 * 1.9 {
+                        bonds.push([i, j]);
+                    }
+                }
+            }
+            bonds
+        }
+    };
+
+    // 侧链上的氢连到 1.3 Å 内最近的侧链重原子
+    let side_heavy: Vec<usize> = bonds.iter().flatten().copied().collect();
+    for (i, atom) in atoms.iter().enumerate() {
+        if !is_hydrogen(atom) {
+            continue;
+        }
+        let nearest = side_heavy.iter().copied().min_by(|&a, &b| {
+            let da = atoms[a].posit.distance_squared(atom.posit);
+            let db = atoms[b].posit.distance_squared(atom.posit);
+            da.total_cmp(&db)
+        });
+        if let Some(j) = nearest
+            && atoms[j].posit.distance_squared(atom.posit) < 1.3 * 1.3
+            && atoms[j].name != "CA"
+        {
+            bonds.push([j, i]);
+        }
+    }
+    bonds
+}
+
+/// Element-colored sticks with rounded joints for one residue's side chain.
+pub(crate) fn sidechain_instances(residue: &Residue, opacity: f32, scale: f32) -> InstanceGroups {
+    let mut groups = InstanceGroups::default();
+    let atoms = &residue.atoms;
+    let color = |a: &ResidueAtom| match a.element {
+        Element::Carbon => Vec3::new(0.75, 0.75, 0.75),
+        ref e => my_color(e),
+    };
+
+    let bonds = sidechain_bonds(residue);
+    let mut joints: Vec<usize> = bonds.iter().flatten().copied().collect();
+    joints.sort_unstable();
+    joints.dedup();
+    for i in joints {
+        let sphere = Sphere::new(atoms[i].posit.to_array(), SIDECHAIN_RADIUS)
+            .color(color(&atoms[i]).into())
+            .opacity(opacity);
+        groups.spheres.push(sphere.to_instance(scale));
+    }
+
+    for [a, b] in bonds {
+        let (pa, pb) = (atoms[a].posit, atoms[b].posit);
+        let mid = (pa + pb) * 0.5;
+        for (end, atom) in [(pa, &atoms[a]), (pb, &atoms[b])] {
+            let stick = Stick::new(end.to_array(), mid.to_array(), SIDECHAIN_RADIUS)
+                .color(color(atom).into())
+                .opacity(opacity);
+            groups.sticks.push(stick.to_instance(scale));
+        }
+    }
+    groups
+}
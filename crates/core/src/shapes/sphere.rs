@@ -4,7 +4,7 @@ use crate::{
     utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle},
 };
 use dashmap::DashMap;
-use glam::Vec3;
+use glam::{Mat3, Mat4, Vec3, Vec4};
 use serde::{Deserialize, Serialize};
 
 use once_cell::sync::Lazy;
@@ -16,6 +16,32 @@ pub struct MeshTemplate {
     pub indices: Vec<u32>,
 }
 
+impl MeshTemplate {
+    /// Places a unit template in the scene, e.g. scaled, rotated and moved
+    /// onto a primitive, with one color for every vertex.
+    pub fn to_mesh(&self, transform: Mat4, color: Vec4, wireframe: bool) -> MeshData {
+        // 非均匀缩放时法线要用逆转置矩阵变换
+        let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+        MeshData {
+            vertices: self
+                .vertices
+                .iter()
+                .map(|v| transform.transform_point3(*v))
+                .collect(),
+            normals: self
+                .normals
+                .iter()
+                .map(|n| (normal_matrix * *n).normalize_or_zero())
+                .collect(),
+            indices: self.indices.clone(),
+            colors: Some(vec![color; self.vertices.len()]),
+            transform: None,
+            is_wireframe: wireframe,
+            ..Default::default()
+        }
+    }
+}
+
 static SPHERE_TEMPLATE_CACHE: Lazy<DashMap<u32, Arc<MeshTemplate>>> = Lazy::new(|| DashMap::new());
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
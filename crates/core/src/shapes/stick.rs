@@ -203,6 +203,8 @@ impl Stick {
             colors: Some(colors),
             transform: None,
             is_wireframe: self.style.wireframe,
+            line_width: self.style.line_width,
+            ..Default::default()
         }
     }
 
@@ -239,6 +241,40 @@ impl Stick {
             color,
         }
     }
+
+    /// Splits the stick into evenly spaced dashes, e.g. for hydrogen bonds or
+    /// distance annotations. The dash pattern is centered so both ends look alike.
+    pub fn to_dashed_instances(
+        &self,
+        scale: f32,
+        dash_length: f32,
+        gap_length: f32,
+    ) -> Vec<StickInstance> {
+        let start = Vec3::from_array(self.start);
+        let end = Vec3::from_array(self.end);
+        let axis = end - start;
+        let length = axis.length();
+        let period = dash_length + gap_length;
+
+        if length <= dash_length || dash_length <= 0.0 || gap_length < 0.0 {
+            return vec![self.to_instance(scale)];
+        }
+
+        let dir = axis / length;
+        let n_dashes = ((length + gap_length) / period).floor().max(1.0) as usize;
+        let offset = (length - (n_dashes as f32 * period - gap_length)) * 0.5;
+
+        (0..n_dashes)
+            .map(|k| {
+                let a = start + dir * (offset + k as f32 * period);
+                let b = a + dir * dash_length;
+                let mut dash = *self;
+                dash.start = a.to_array();
+                dash.end = b.to_array();
+                dash.to_instance(scale)
+            })
+            .collect()
+    }
 }
 
 impl VisualShape for Stick {
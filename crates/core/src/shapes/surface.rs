@@ -0,0 +1,411 @@
+use crate::Shape;
+use crate::parser::sasa::sasa_radius;
+use crate::shapes::molecules::my_color;
+use crate::shapes::{Molecule, Protein, VolumeGrid};
+use crate::utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle};
+use glam::Vec3;
+use kiddo::{KdTree, SquaredEuclidean};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on grid points; coarser spacing is used for very large inputs.
+const MAX_GRID_POINTS: usize = 16_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SurfaceKind {
+    /// Smooth sum of atom-centered Gaussians.
+    Gaussian,
+    /// Solvent-excluded surface traced by a rolling probe sphere.
+    SolventExcluded,
+}
+
+/// Molecular surface extracted with marching cubes, colored by the nearest atom.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Surface {
+    pub kind: SurfaceKind,
+    pub atom_posits: Vec<Vec3>,
+    pub atom_radii: Vec<f32>,
+    pub atom_colors: Vec<Vec3>,
+    /// Solvent probe radius in Å (solvent-excluded surface only).
+    pub probe_radius: f32,
+    /// Grid spacing in Å.
+    pub resolution: f32,
+    /// Gaussian falloff; larger values follow the atom spheres more tightly.
+    pub smoothness: f32,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+
+    /// Mesh and the nearest atom of every vertex. The mesh is uncolored except on
+    /// interpolated frames, which carry blended vertex colors.
+    #[serde(skip)]
+    mesh_cache: OnceCell<(MeshData, Vec<u32>)>,
+}
+
+impl Interpolatable for Surface {
+    fn interpolate(&self, other: &Self, t: f32, logger: impl Logger) -> Self {
+        if self.atom_posits.len() != other.atom_posits.len() {
+            logger.warn(format!(
+                "Surface interpolation skipped: atom count differs (self: {}, other: {})",
+                self.atom_posits.len(),
+                other.atom_posits.len()
+            ));
+            return self.clone();
+        }
+
+        // 网格按关键帧缓存；顶点和索引数相同才逐点插值，否则在中点切换，避免每帧重跑 marching cubes
+        let (mesh_a, nearest_a) = self.get_mesh();
+        let (mesh_b, nearest_b) = other.get_mesh();
+        if mesh_a.vertices.len() != mesh_b.vertices.len()
+            || mesh_a.indices.len() != mesh_b.indices.len()
+        {
+            return if t < 0.5 { self.clone() } else { other.clone() };
+        }
+        let colors_a = self.vertex_colors(mesh_a, nearest_a);
+        let colors_b = other.vertex_colors(mesh_b, nearest_b);
+        let mesh = MeshData {
+            vertices: mesh_a
+                .vertices
+                .iter()
+                .zip(&mesh_b.vertices)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+            normals: mesh_a
+                .normals
+                .iter()
+                .zip(&mesh_b.normals)
+                .map(|(a, b)| a.lerp(*b, t).normalize_or_zero())
+                .collect(),
+            colors: Some(
+                colors_a
+                    .iter()
+                    .zip(&colors_b)
+                    .map(|(a, b)| a.lerp(*b, t).extend(1.0))
+                    .collect(),
+            ),
+            ..mesh_a.clone()
+        };
+
+        Self {
+            atom_posits: self
+                .atom_posits
+                .iter()
+                .zip(&other.atom_posits)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+            atom_colors: self
+                .atom_colors
+                .iter()
+                .zip(&other.atom_colors)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+            mesh_cache: OnceCell::with_value((mesh, nearest_a.clone())),
+            ..self.clone()
+        }
+    }
+}
+
+impl Into<Shape> for Surface {
+    fn into(self) -> Shape {
+        Shape::Surface(self)
+    }
+}
+
+impl VisualShape for Surface {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Surface {
+    /// Surface around arbitrary spheres; `colors` are used per atom for the vertices.
+    pub fn new(posits: Vec<Vec3>, radii: Vec<f32>, colors: Vec<Vec3>) -> Self {
+        Self {
+            kind: SurfaceKind::SolventExcluded,
+            atom_posits: posits,
+            atom_radii: radii,
+            atom_colors: colors,
+            probe_radius: 1.4,
+            resolution: 0.5,
+            smoothness: 2.0,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+            mesh_cache: OnceCell::new(),
+        }
+    }
+
+    pub fn from_molecule(molecule: &Molecule) -> Self {
+        let radii = molecule.atom_types.iter().map(sasa_radius).collect();
+        let colors = (0..molecule.atom_posits.len())
+            .map(|i| molecule.get_atom_colors(i))
+            .collect();
+        Self::new(molecule.atom_posits.clone(), radii, colors)
+    }
+
+    /// Surface of all protein atoms, side chains included, tinted with the protein color.
+    pub fn from_protein(protein: &Protein) -> Self {
+        let mut posits = Vec::new();
+        let mut radii = Vec::new();
+        let mut colors = Vec::new();
+        for res in protein.chains.iter().flat_map(|c| &c.residues) {
+            // 旧数据可能没有保存残基原子，退回主链
+            let atoms = if res.atoms.is_empty() {
+                res.real_backbone()
+            } else {
+                res.atoms.iter().map(|a| (a.posit, a.element)).collect()
+            };
+            for (p, e) in atoms {
+                posits.push(p);
+                radii.push(sasa_radius(&e));
+                colors.push(protein.style.color.unwrap_or_else(|| my_color(&e)));
+            }
+        }
+        Self::new(posits, radii, colors)
+    }
+
+    pub fn kind(mut self, kind: SurfaceKind) -> Self {
+        self.kind = kind;
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    pub fn probe_radius(mut self, probe_radius: f32) -> Self {
+        self.probe_radius = probe_radius.max(0.0);
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    pub fn resolution(mut self, resolution: f32) -> Self {
+        self.resolution = resolution.max(0.1);
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    pub fn smoothness(mut self, smoothness: f32) -> Self {
+        self.smoothness = smoothness.max(0.1);
+        self.mesh_cache = OnceCell::new();
+        self
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let (mesh, nearest) = self.get_mesh();
+        let alpha = self.style.opacity.clamp(0.0, 1.0);
+        let colors = match self.style.color {
+            Some(color) => vec![color.extend(alpha); mesh.vertices.len()],
+            None => self
+                .vertex_colors(mesh, nearest)
+                .into_iter()
+                .map(|c| c.extend(alpha))
+                .collect(),
+        };
+
+        MeshData {
+            vertices: mesh.vertices.iter().map(|v| *v * scale).collect(),
+            colors: Some(colors),
+            ..mesh.clone()
+        }
+    }
+
+    /// Color of every vertex: the blended colors of an interpolated frame, otherwise
+    /// the color of the nearest atom.
+    fn vertex_colors(&self, mesh: &MeshData, nearest: &[u32]) -> Vec<Vec3> {
+        if let Some(colors) = &mesh.colors {
+            return colors.iter().map(|c| c.truncate()).collect();
+        }
+        nearest
+            .iter()
+            .map(|&i| {
+                self.atom_colors
+                    .get(i as usize)
+                    .copied()
+                    .unwrap_or(Vec3::ONE)
+            })
+            .collect()
+    }
+
+    /// Unscaled surface mesh and the index of the atom nearest to each vertex,
+    /// computed on first use.
+    pub fn get_mesh(&self) -> &(MeshData, Vec<u32>) {
+        self.mesh_cache.get_or_init(|| {
+            let n = self.atom_posits.len().min(self.atom_radii.len());
+            if n == 0 {
+                return Default::default();
+            }
+            let mesh = match self.kind {
+                SurfaceKind::Gaussian => self.gaussian_grid().marching_cubes(1.0),
+                SurfaceKind::SolventExcluded => self.ses_grid().marching_cubes(0.0),
+            };
+
+            let tree = self.atom_tree();
+            let nearest = mesh
+                .vertices
+                .iter()
+                .map(|v| tree.nearest_one::<SquaredEuclidean>(&v.to_array()).item as u32)
+                .collect();
+            (mesh, nearest)
+        })
+    }
+
+    fn atom_tree(&self) -> KdTree<f32, 3> {
+        let mut tree: KdTree<f32, 3> = KdTree::new();
+        for (i, p) in self.atom_posits.iter().enumerate() {
+            tree.add(&p.to_array(), i as u64);
+        }
+        tree
+    }
+
+    /// Empty grid covering all atoms plus `padding` Å.
+    fn empty_grid(&self, padding: f32, fill: f32) -> VolumeGrid {
+        let (min, max) = self
+            .atom_posits
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(lo, hi), p| {
+                (lo.min(*p), hi.max(*p))
+            });
+        let min = min - Vec3::splat(padding);
+        let size = max - min + Vec3::splat(padding);
+
+        let mut spacing = self.resolution;
+        let volume = size.x * size.y * size.z;
+        if volume / spacing.powi(3) > MAX_GRID_POINTS as f32 {
+            spacing = (volume / MAX_GRID_POINTS as f32).cbrt();
+        }
+        let dims = (size / spacing)
+            .ceil()
+            .as_uvec3()
+            .to_array()
+            .map(|d| d as usize + 1);
+        VolumeGrid::new(dims, min, spacing, fill)
+    }
+
+    /// Visits every grid point within `radius` of `center`.
+    fn for_each_near(
+        grid: &mut VolumeGrid,
+        center: Vec3,
+        radius: f32,
+        mut f: impl FnMut(&mut f32, f32),
+    ) {
+        let spacing = grid.axes[0].x;
+        let lo = ((center - Vec3::splat(radius) - grid.origin) / spacing)
+            .floor()
+            .max(Vec3::ZERO)
+            .as_uvec3();
+        let hi = ((center + Vec3::splat(radius) - grid.origin) / spacing)
+            .ceil()
+            .as_uvec3()
+            .min(glam::UVec3::from_array(grid.dims.map(|d| d as u32 - 1)));
+
+        for k in lo.z..=hi.z {
+            for j in lo.y..=hi.y {
+                for i in lo.x..=hi.x {
+                    let (i, j, k) = (i as usize, j as usize, k as usize);
+                    let d_sq = grid.position(i, j, k).distance_squared(center);
+                    if d_sq <= radius * radius {
+                        let idx = grid.index(i, j, k);
+                        f(&mut grid.values[idx], d_sq);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Density `Σ exp(-k (d²/r² - 1))`, which equals 1 on an isolated atom's vdW sphere.
+    fn gaussian_grid(&self) -> VolumeGrid {
+        let k = self.smoothness;
+        // 贡献小于 1e-3 的部分忽略
+        let cutoff_factor = (1.0 + (1e3f32).ln() / k).sqrt();
+        let max_r = self.atom_radii.iter().copied().fold(0.0, f32::max);
+        let mut grid = self.empty_grid(max_r * cutoff_factor, 0.0);
+
+        for (p, r) in self.atom_posits.iter().zip(&self.atom_radii) {
+            let inv_r_sq = 1.0 / (r * r);
+            Self::for_each_near(&mut grid, *p, r * cutoff_factor, |v, d_sq| {
+                *v += (-k * (d_sq * inv_r_sq - 1.0)).exp();
+            });
+        }
+        grid
+    }
+
+    /// Field that is positive inside the solvent-excluded surface and zero on it.
+    ///
+    /// Probe centres are collected on the solvent accessible surface; the SES is
+    /// what stays farther than one probe radius from all of them.
+    fn ses_grid(&self) -> VolumeGrid {
+        let probe = self.probe_radius;
+        let max_r = self.atom_radii.iter().copied().fold(0.0, f32::max);
+        let mut grid = self.empty_grid(max_r + probe + 2.0 * self.resolution, f32::MAX);
+        let spacing = grid.axes[0].x;
+
+        // 1. 到 SAS 的有符号距离，负值在内部
+        for (p, r) in self.atom_posits.iter().zip(&self.atom_radii) {
+            let r_sas = r + probe;
+            Self::for_each_near(&mut grid, *p, r_sas + 2.0 * spacing, |v, d_sq| {
+                *v = v.min(d_sq.sqrt() - r_sas);
+            });
+        }
+        let sas = grid.values.clone();
+
+        if probe <= 0.0 {
+            grid.values.iter_mut().for_each(|v| *v = -v.min(spacing));
+            return grid;
+        }
+
+        // 2. SAS 边界上的探针球心，投影到最近原子的 SAS 球面上
+        let tree = self.atom_tree();
+        let [nx, ny, nz] = grid.dims;
+        let mut probes = Vec::new();
+        for k in 0..nz {
+            for j in 0..ny {
+                for i in 0..nx {
+                    if sas[grid.index(i, j, k)] < 0.0 {
+                        continue;
+                    }
+                    let inside = |i: usize, j: usize, k: usize| sas[grid.index(i, j, k)] < 0.0;
+                    let touches_inside = (i > 0 && inside(i - 1, j, k))
+                        || (i + 1 < nx && inside(i + 1, j, k))
+                        || (j > 0 && inside(i, j - 1, k))
+                        || (j + 1 < ny && inside(i, j + 1, k))
+                        || (k > 0 && inside(i, j, k - 1))
+                        || (k + 1 < nz && inside(i, j, k + 1));
+                    if !touches_inside {
+                        continue;
+                    }
+
+                    let x = grid.position(i, j, k);
+                    let nearest = tree.nearest_one::<SquaredEuclidean>(&x.to_array());
+                    let a = nearest.item as usize;
+                    let dir = (x - self.atom_posits[a]).normalize_or_zero();
+                    let on_sas = self.atom_posits[a] + dir * (self.atom_radii[a] + probe);
+                    // 投影后仍需在所有原子的 SAS 之外
+                    let buried = tree
+                        .within_unsorted::<SquaredEuclidean>(
+                            &on_sas.to_array(),
+                            (max_r + probe).powi(2),
+                        )
+                        .iter()
+                        .any(|nb| {
+                            let b = nb.item as usize;
+                            let r = self.atom_radii[b] + probe - 1e-3;
+                            b != a && nb.distance < r * r
+                        });
+                    probes.push(if buried { x } else { on_sas });
+                }
+            }
+        }
+
+        // 3. 离所有探针球心都超过探针半径的 SAS 内部点属于 SES 内部
+        for (v, s) in grid.values.iter_mut().zip(&sas) {
+            *v = if *s < 0.0 { probe } else { -probe };
+        }
+        for c in probes {
+            Self::for_each_near(&mut grid, c, probe + 2.0 * spacing, |v, d_sq| {
+                *v = v.min(d_sq.sqrt() - probe);
+            });
+        }
+        grid
+    }
+}
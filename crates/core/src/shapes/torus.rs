@@ -0,0 +1,158 @@
+use crate::Arc;
+use dashmap::DashMap;
+use glam::{Mat4, Quat, Vec3};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+use crate::{
+    Shape,
+    shapes::sphere::MeshTemplate,
+    utils::{Interaction, Interpolatable, Logger, MeshData, VisualShape, VisualStyle},
+};
+
+/// Keyed by quality and the tube/ring radius ratio in thousandths.
+static TORUS_TEMPLATE_CACHE: Lazy<DashMap<(u32, u32), Arc<MeshTemplate>>> = Lazy::new(DashMap::new);
+
+/// Ring around `normal`, e.g. to mark a rotation axis or an aromatic ring.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Torus {
+    pub center: [f32; 3],
+    /// Axis the ring goes around.
+    pub normal: [f32; 3],
+    /// Distance from the center to the middle of the tube.
+    pub radius: f32,
+    pub tube_radius: f32,
+    pub quality: u32,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for Torus {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        let lerp = |a: f32, b: f32| a * (1.0 - t) + b * t;
+        Self {
+            center: Vec3::from(self.center)
+                .lerp(Vec3::from(other.center), t)
+                .to_array(),
+            normal: Vec3::from(self.normal)
+                .lerp(Vec3::from(other.normal), t)
+                .to_array(),
+            radius: lerp(self.radius, other.radius),
+            tube_radius: lerp(self.tube_radius, other.tube_radius),
+            ..*self
+        }
+    }
+}
+
+impl Into<Shape> for Torus {
+    fn into(self) -> Shape {
+        Shape::Torus(self)
+    }
+}
+
+impl VisualShape for Torus {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl Torus {
+    pub fn new(center: [f32; 3], normal: [f32; 3], radius: f32, tube_radius: f32) -> Self {
+        Self {
+            center,
+            normal,
+            radius,
+            tube_radius,
+            quality: 2,
+            style: VisualStyle {
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    pub fn set_center(mut self, center: [f32; 3]) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn set_normal(mut self, normal: [f32; 3]) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn set_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn set_tube_radius(mut self, tube_radius: f32) -> Self {
+        self.tube_radius = tube_radius;
+        self
+    }
+
+    /// Torus around +Z with ring radius 1 and the given tube radius.
+    pub fn get_or_generate_torus_mesh_template(quality: u32, tube_ratio: f32) -> Arc<MeshTemplate> {
+        let key = (quality, (tube_ratio * 1000.0).round() as u32);
+        if let Some(entry) = TORUS_TEMPLATE_CACHE.get(&key) {
+            return Arc::clone(entry.value());
+        }
+
+        let ring_segments = 20 * quality.max(1);
+        let tube_segments = 10 * quality.max(1);
+        let tube = key.1 as f32 / 1000.0;
+
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut indices = Vec::new();
+
+        for i in 0..=ring_segments {
+            let theta = TAU * i as f32 / ring_segments as f32;
+            let radial = Vec3::new(theta.cos(), theta.sin(), 0.0);
+            for j in 0..=tube_segments {
+                let phi = TAU * j as f32 / tube_segments as f32;
+                let normal = radial * phi.cos() + Vec3::Z * phi.sin();
+                vertices.push(radial + normal * tube);
+                normals.push(normal);
+            }
+        }
+
+        let row = tube_segments + 1;
+        for i in 0..ring_segments {
+            for j in 0..tube_segments {
+                let a = i * row + j;
+                let b = a + row;
+                indices.extend([a, b, a + 1, a + 1, b, b + 1]);
+            }
+        }
+
+        let template = Arc::new(MeshTemplate {
+            vertices,
+            normals,
+            indices,
+        });
+        TORUS_TEMPLATE_CACHE.insert(key, Arc::clone(&template));
+        template
+    }
+
+    pub fn to_mesh(&self, scale: f32) -> MeshData {
+        let radius = self.radius.max(1e-6);
+        let rotation =
+            Quat::from_rotation_arc(Vec3::Z, Vec3::from(self.normal).normalize_or(Vec3::Z));
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::splat(radius * scale),
+            rotation,
+            Vec3::from(self.center) * scale,
+        );
+        let color = self.style.color.unwrap_or(Vec3::ONE);
+        Self::get_or_generate_torus_mesh_template(self.quality, self.tube_radius / radius).to_mesh(
+            transform,
+            color.extend(self.style.opacity.clamp(0.0, 1.0)),
+            self.style.wireframe,
+        )
+    }
+}
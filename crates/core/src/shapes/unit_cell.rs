@@ -0,0 +1,202 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::io::ErrorKind;
+
+use crate::Shape;
+use crate::parser::ccp4::{Ccp4Map, cell_matrix};
+use crate::parser::cif::Cif;
+use crate::parser::cube::Cube;
+use crate::parser::mmcif::MmCif;
+use crate::parser::pdb::Pdb;
+use crate::shapes::StickInstance;
+use crate::utils::{
+    InstanceGroups, Interaction, Interpolatable, IntoInstanceGroups, Logger, MeshData, VisualShape,
+    VisualStyle,
+};
+
+/// Colors of the a, b and c edges when [`UnitCell::color_axes`] is on.
+const AXIS_COLORS: [[f32; 3]; 3] = [[0.9, 0.2, 0.2], [0.2, 0.8, 0.2], [0.2, 0.4, 1.0]];
+
+/// Crystal unit cell or simulation box, drawn as a wireframe of thin sticks.
+///
+/// With [`UnitCell::repeat`] the wireframe covers an NxMxK block of cells,
+/// matching a molecule replicated by [`Molecule::supercell`](crate::shapes::Molecule::supercell).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnitCell {
+    pub origin: Vec3,
+    /// Cell vectors a, b and c in Å.
+    pub vectors: [Vec3; 3],
+    /// Number of cells drawn along a, b and c.
+    pub repeat: [usize; 3],
+    pub radius: f32,
+    /// Draw the a, b and c edges at the origin in red, green and blue.
+    pub color_axes: bool,
+
+    pub style: VisualStyle,
+    pub interaction: Interaction,
+}
+
+impl Interpolatable for UnitCell {
+    fn interpolate(&self, other: &Self, t: f32, _logger: impl Logger) -> Self {
+        // NPT 模拟中盒子随时间变化，向量逐个插值
+        Self {
+            origin: self.origin.lerp(other.origin, t),
+            vectors: [0, 1, 2].map(|i| self.vectors[i].lerp(other.vectors[i], t)),
+            radius: self.radius * (1.0 - t) + other.radius * t,
+            ..self.clone()
+        }
+    }
+}
+
+impl Into<Shape> for UnitCell {
+    fn into(self) -> Shape {
+        Shape::UnitCell(self)
+    }
+}
+
+impl VisualShape for UnitCell {
+    fn style_mut(&mut self) -> &mut VisualStyle {
+        &mut self.style
+    }
+}
+
+impl UnitCell {
+    /// Cell from lengths a, b, c (Å) and angles alpha, beta, gamma (degrees),
+    /// with a along X and b in the XY plane.
+    pub fn new(params: [f32; 6]) -> Self {
+        let m = cell_matrix(params);
+        Self::from_vectors(
+            m.x_axis.to_array(),
+            m.y_axis.to_array(),
+            m.z_axis.to_array(),
+        )
+    }
+
+    /// Box from its three edge vectors, e.g. the box vectors of an MD frame.
+    pub fn from_vectors(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> Self {
+        Self {
+            origin: Vec3::ZERO,
+            vectors: [a.into(), b.into(), c.into()],
+            repeat: [1, 1, 1],
+            radius: 0.04,
+            color_axes: false,
+            style: VisualStyle {
+                color: Some(Vec3::splat(0.55)),
+                opacity: 1.0,
+                visible: true,
+                ..Default::default()
+            },
+            interaction: Default::default(),
+        }
+    }
+
+    /// Reads `_cell` from an mmCIF file.
+    pub fn from_mmcif(mmcif: &str) -> io::Result<Self> {
+        let cell = MmCif::new(mmcif)?
+            .cell
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "mmCIF has no _cell"))?;
+        Ok(Self::new(cell))
+    }
+
+    /// Reads the CRYST1 record of a PDB file.
+    pub fn from_pdb(pdb: &str) -> io::Result<Self> {
+        let cell = Pdb::new(pdb)?
+            .cell
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "PDB has no CRYST1"))?;
+        Ok(Self::new(cell))
+    }
+
+    /// Reads `_cell_*` from a small-molecule CIF file.
+    pub fn from_cif(cif: &str) -> io::Result<Self> {
+        Ok(Self::new(Cif::new(cif)?.cell))
+    }
+
+    /// Unit cell of a CCP4/MRC map.
+    pub fn from_ccp4(bytes: &[u8]) -> io::Result<Self> {
+        Ok(Self::new(Ccp4Map::new(bytes)?.cell))
+    }
+
+    /// Box spanned by the volumetric grid of a Gaussian cube file.
+    pub fn from_cube(cube: &str) -> io::Result<Self> {
+        let cube = Cube::new(cube)?;
+        let grid = cube
+            .volumes
+            .first()
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "No volumetric data"))?;
+        let [a, b, c] = [0, 1, 2].map(|i| (grid.axes[i] * grid.dims[i] as f32).to_array());
+        Ok(Self::from_vectors(a, b, c).set_origin(grid.origin.to_array()))
+    }
+
+    pub fn set_origin(mut self, origin: [f32; 3]) -> Self {
+        self.origin = origin.into();
+        self
+    }
+
+    /// Draws an NxMxK block of cells.
+    pub fn repeat(mut self, repeat: [usize; 3]) -> Self {
+        self.repeat = repeat.map(|n| n.max(1));
+        self
+    }
+
+    pub fn set_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn color_axes(mut self, color_axes: bool) -> Self {
+        self.color_axes = color_axes;
+        self
+    }
+
+    /// Offset of the cell with integer index `[i, j, k]` from the origin cell.
+    pub fn translation(&self, [i, j, k]: [i32; 3]) -> Vec3 {
+        let [a, b, c] = self.vectors;
+        a * i as f32 + b * j as f32 + c * k as f32
+    }
+
+    pub fn get_center(&self) -> [f32; 3] {
+        let [n, m, k] = self.repeat.map(|n| n as i32);
+        (self.origin + self.translation([n, m, k]) * 0.5).to_array()
+    }
+
+    pub fn to_mesh(&self, _scale: f32) -> MeshData {
+        MeshData::default()
+    }
+}
+
+impl IntoInstanceGroups for UnitCell {
+    fn to_instance_group(&self, scale: f32) -> InstanceGroups {
+        let mut groups = InstanceGroups::default();
+        if !self.style.visible {
+            return groups;
+        }
+
+        let base_color = self.style.color.unwrap_or(Vec3::splat(0.55));
+        let alpha = self.style.opacity.clamp(0.0, 1.0);
+        let radius = self.radius * scale;
+
+        // 格子线沿每个晶轴方向各画一组，相邻晶胞共享的棱只画一次
+        for (axis, axis_color) in AXIS_COLORS.iter().enumerate() {
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            let length = self.vectors[axis] * self.repeat[axis] as f32;
+            for p in 0..=self.repeat[u] {
+                for q in 0..=self.repeat[v] {
+                    let start =
+                        self.origin + self.vectors[u] * p as f32 + self.vectors[v] * q as f32;
+                    let color = match self.color_axes && p == 0 && q == 0 {
+                        true => Vec3::from(*axis_color),
+                        false => base_color,
+                    };
+                    groups.sticks.push(StickInstance::new(
+                        (start * scale).to_array(),
+                        ((start + length) * scale).to_array(),
+                        radius,
+                        color.extend(alpha).to_array(),
+                    ));
+                }
+            }
+        }
+        groups
+    }
+}
@@ -0,0 +1,322 @@
+use crate::utils::MeshData;
+use glam::Vec3;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Scalar field sampled on a regular (possibly skewed) 3D grid.
+///
+/// Point `(i, j, k)` sits at `origin + i * axes[0] + j * axes[1] + k * axes[2]`,
+/// values are stored with `i` varying fastest.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VolumeGrid {
+    pub dims: [usize; 3],
+    pub origin: Vec3,
+    pub axes: [Vec3; 3],
+    pub values: Vec<f32>,
+}
+
+// 立方体顶点编号与 Paul Bourke 的经典 marching cubes 表一致
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [1, 1, 0],
+    [0, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [1, 1, 1],
+    [0, 1, 1],
+];
+
+const EDGES: [[usize; 2]; 12] = [
+    [0, 1],
+    [1, 2],
+    [2, 3],
+    [3, 0],
+    [4, 5],
+    [5, 6],
+    [6, 7],
+    [7, 4],
+    [0, 4],
+    [1, 5],
+    [2, 6],
+    [3, 7],
+];
+
+// 每个面的四个顶点按环绕顺序排列
+const FACES: [[usize; 4]; 6] = [
+    [0, 1, 2, 3],
+    [4, 5, 6, 7],
+    [0, 1, 5, 4],
+    [3, 2, 6, 7],
+    [0, 3, 7, 4],
+    [1, 2, 6, 5],
+];
+
+/// Triangles (as cube edge indices) for each of the 256 corner configurations.
+///
+/// Generated by walking the iso-line segments across the six faces. Ambiguous
+/// faces always separate the inside corners, which only depends on the face
+/// itself, so neighbouring cubes agree and the surface stays watertight.
+static CASES: Lazy<Vec<Vec<[usize; 3]>>> = Lazy::new(|| (0..256).map(triangulate_case).collect());
+
+fn edge_index(a: usize, b: usize) -> usize {
+    EDGES
+        .iter()
+        .position(|e| (e[0] == a && e[1] == b) || (e[0] == b && e[1] == a))
+        .unwrap()
+}
+
+fn triangulate_case(case: usize) -> Vec<[usize; 3]> {
+    let inside = |c: usize| case & (1 << c) != 0;
+    let corner = |c: usize| Vec3::from_array(CORNERS[c].map(|x| x as f32));
+    let midpoint = |e: usize| (corner(EDGES[e][0]) + corner(EDGES[e][1])) * 0.5;
+
+    // 每条被穿过的棱在两个相邻面上各连接一条棱：links[e] = [(另一条棱, 面)]
+    let mut links: [Vec<(usize, usize)>; 12] = Default::default();
+    for (f, face) in FACES.iter().enumerate() {
+        let edges: [usize; 4] = std::array::from_fn(|i| edge_index(face[i], face[(i + 1) % 4]));
+        let crossed: Vec<usize> = (0..4)
+            .filter(|&i| inside(face[i]) != inside(face[(i + 1) % 4]))
+            .collect();
+
+        let mut connect = |a: usize, b: usize| {
+            links[edges[a]].push((edges[b], f));
+            links[edges[b]].push((edges[a], f));
+        };
+        match crossed.len() {
+            2 => connect(crossed[0], crossed[1]),
+            4 => {
+                // 对角歧义面：把两个内部顶点各自切开
+                for (i, &c) in face.iter().enumerate() {
+                    if inside(c) {
+                        connect((i + 3) % 4, i);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut visited = [false; 12];
+    let mut triangles = Vec::new();
+    for start in 0..12 {
+        if visited[start] || links[start].is_empty() {
+            continue;
+        }
+        let mut polygon = vec![start];
+        visited[start] = true;
+        let (mut prev, mut cur) = (start, links[start][0].0);
+        while cur != start {
+            visited[cur] = true;
+            polygon.push(cur);
+            let next = if links[cur][0].0 == prev {
+                links[cur][1].0
+            } else {
+                links[cur][0].0
+            };
+            prev = cur;
+            cur = next;
+        }
+
+        // 定向：法线指向低值一侧时多边形为逆时针。
+        // 对面上的线段 a→b（面外法线 m），内部顶点应位于 (b - a) × m 一侧
+        let (a, b) = (polygon[0], polygon[1]);
+        let face = FACES[links[a][0].1];
+        let m = face.iter().map(|&c| corner(c)).sum::<Vec3>() / 4.0 - Vec3::splat(0.5);
+        let shared = EDGES[a].iter().find(|c| EDGES[b].contains(c)).copied();
+        let (reference, sign) = match shared {
+            Some(c) if inside(c) => (corner(c), 1.0),
+            Some(c) => (corner(c), -1.0),
+            None => (corner(*face.iter().find(|&&c| inside(c)).unwrap()), 1.0),
+        };
+        let side = (midpoint(b) - midpoint(a))
+            .cross(m)
+            .dot(reference - midpoint(a));
+        if side * sign < 0.0 {
+            polygon.reverse();
+        }
+
+        for i in 1..polygon.len() - 1 {
+            triangles.push([polygon[0], polygon[i], polygon[i + 1]]);
+        }
+    }
+    triangles
+}
+
+impl VolumeGrid {
+    /// Axis-aligned grid filled with `fill`.
+    pub fn new(dims: [usize; 3], origin: Vec3, spacing: f32, fill: f32) -> Self {
+        Self {
+            dims,
+            origin,
+            axes: [Vec3::X * spacing, Vec3::Y * spacing, Vec3::Z * spacing],
+            values: vec![fill; dims[0] * dims[1] * dims[2]],
+        }
+    }
+
+    #[inline]
+    pub fn index(&self, i: usize, j: usize, k: usize) -> usize {
+        i + self.dims[0] * (j + self.dims[1] * k)
+    }
+
+    #[inline]
+    pub fn value(&self, i: usize, j: usize, k: usize) -> f32 {
+        self.values[self.index(i, j, k)]
+    }
+
+    #[inline]
+    pub fn position(&self, i: usize, j: usize, k: usize) -> Vec3 {
+        self.origin + self.axes[0] * i as f32 + self.axes[1] * j as f32 + self.axes[2] * k as f32
+    }
+
+    /// Mean and standard deviation of all values.
+    pub fn mean_and_sigma(&self) -> (f32, f32) {
+        let n = self.values.len().max(1) as f64;
+        let mean = self.values.iter().map(|&v| v as f64).sum::<f64>() / n;
+        let var = self
+            .values
+            .iter()
+            .map(|&v| (v as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        (mean as f32, var.sqrt() as f32)
+    }
+
+    /// Sub-grid covering `points` plus `padding` Å.
+    ///
+    /// With `periodic`, indices past the grid edges wrap around, which is what a
+    /// crystallographic map covering one whole unit cell needs.
+    pub fn crop_to_points(&self, points: &[Vec3], padding: f32, periodic: bool) -> VolumeGrid {
+        let to_grid = glam::Mat3::from_cols(self.axes[0], self.axes[1], self.axes[2]).inverse();
+        // 半径为 padding 的球在第 i 个网格坐标上的投影半宽为 padding * |第 i 行|
+        let pad = Vec3::new(
+            to_grid.row(0).length(),
+            to_grid.row(1).length(),
+            to_grid.row(2).length(),
+        ) * padding;
+
+        let (lo, hi) = points.iter().fold((Vec3::MAX, Vec3::MIN), |(lo, hi), p| {
+            let f = to_grid * (*p - self.origin);
+            (lo.min(f - pad), hi.max(f + pad))
+        });
+        if points.is_empty() {
+            return self.clone();
+        }
+
+        let mut lo = lo.floor().as_ivec3().to_array();
+        let mut hi = hi.ceil().as_ivec3().to_array();
+        if !periodic {
+            for d in 0..3 {
+                lo[d] = lo[d].clamp(0, self.dims[d] as i32 - 1);
+                hi[d] = hi[d].clamp(0, self.dims[d] as i32 - 1);
+            }
+        }
+        let dims: [usize; 3] = std::array::from_fn(|d| (hi[d] - lo[d] + 1).max(0) as usize);
+
+        let wrap = |x: i32, d: usize| x.rem_euclid(self.dims[d] as i32) as usize;
+        let mut values = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+        for k in lo[2]..=hi[2] {
+            for j in lo[1]..=hi[1] {
+                for i in lo[0]..=hi[0] {
+                    values.push(self.value(wrap(i, 0), wrap(j, 1), wrap(k, 2)));
+                }
+            }
+        }
+
+        VolumeGrid {
+            dims,
+            origin: self.origin
+                + self.axes[0] * lo[0] as f32
+                + self.axes[1] * lo[1] as f32
+                + self.axes[2] * lo[2] as f32,
+            axes: self.axes,
+            values,
+        }
+    }
+
+    /// Gradient in grid coordinates, by central differences.
+    fn gradient(&self, i: usize, j: usize, k: usize) -> Vec3 {
+        let d = |c: usize, n: usize| (c.saturating_sub(1), (c + 1).min(n - 1));
+        let (i0, i1) = d(i, self.dims[0]);
+        let (j0, j1) = d(j, self.dims[1]);
+        let (k0, k1) = d(k, self.dims[2]);
+        Vec3::new(
+            (self.value(i1, j, k) - self.value(i0, j, k)) / (i1 - i0).max(1) as f32,
+            (self.value(i, j1, k) - self.value(i, j0, k)) / (j1 - j0).max(1) as f32,
+            (self.value(i, j, k1) - self.value(i, j, k0)) / (k1 - k0).max(1) as f32,
+        )
+    }
+
+    /// Extracts the `iso` surface with marching cubes.
+    ///
+    /// Values above `iso` count as inside; normals point towards lower values.
+    /// Vertices are shared between neighbouring cubes, no colors are set.
+    pub fn marching_cubes(&self, iso: f32) -> MeshData {
+        let [nx, ny, nz] = self.dims;
+        let mut mesh = MeshData::default();
+        if nx < 2 || ny < 2 || nz < 2 {
+            return mesh;
+        }
+
+        // 把网格坐标下的梯度换算到世界坐标：g_world = (A^T)^-1 * g_grid
+        let axes = glam::Mat3::from_cols(self.axes[0], self.axes[1], self.axes[2]);
+        let grad_to_world = axes.transpose().inverse();
+        // 左手系网格（如轴顺序为奇置换）下三角形绕向需要翻转
+        let flip = axes.determinant() < 0.0;
+
+        // key = 起点网格索引 * 3 + 轴
+        let mut edge_vertices: HashMap<usize, u32> = HashMap::new();
+
+        for k in 0..nz - 1 {
+            for j in 0..ny - 1 {
+                for i in 0..nx - 1 {
+                    let mut case = 0;
+                    for (c, o) in CORNERS.iter().enumerate() {
+                        if self.value(i + o[0], j + o[1], k + o[2]) > iso {
+                            case |= 1 << c;
+                        }
+                    }
+                    if case == 0 || case == 255 {
+                        continue;
+                    }
+
+                    for tri in &CASES[case] {
+                        let ids = tri.map(|e| {
+                            let [a, b] = EDGES[e];
+                            let (ca, cb) = (CORNERS[a], CORNERS[b]);
+                            let axis = (0..3).find(|&d| ca[d] != cb[d]).unwrap();
+                            let lo = if ca[axis] < cb[axis] { ca } else { cb };
+                            let p = [i + lo[0], j + lo[1], k + lo[2]];
+                            let key = self.index(p[0], p[1], p[2]) * 3 + axis;
+
+                            *edge_vertices.entry(key).or_insert_with(|| {
+                                let mut q = p;
+                                q[axis] += 1;
+                                let (v0, v1) =
+                                    (self.value(p[0], p[1], p[2]), self.value(q[0], q[1], q[2]));
+                                let t = ((iso - v0) / (v1 - v0)).clamp(0.0, 1.0);
+                                let pos = self
+                                    .position(p[0], p[1], p[2])
+                                    .lerp(self.position(q[0], q[1], q[2]), t);
+                                let g = self
+                                    .gradient(p[0], p[1], p[2])
+                                    .lerp(self.gradient(q[0], q[1], q[2]), t);
+                                mesh.vertices.push(pos);
+                                mesh.normals.push(-(grad_to_world * g).normalize_or_zero());
+                                mesh.vertices.len() as u32 - 1
+                            })
+                        });
+
+                        if flip {
+                            mesh.indices.extend([ids[0], ids[2], ids[1]]);
+                        } else {
+                            mesh.indices.extend(ids);
+                        }
+                    }
+                }
+            }
+        }
+        mesh
+    }
+}
@@ -1,7 +1,10 @@
 use glam::{Mat4, Vec3, Vec4};
 use serde::{Deserialize, Serialize};
 
-use crate::shapes::{Molecule, Protein, Sphere, SphereInstance, Stick, StickInstance};
+use crate::shapes::{
+    Arrow, Cone, Cuboid, Ellipsoid, Isosurface, Labels, Measurement, Mesh, Molecule, Plane,
+    Protein, Sphere, SphereInstance, Stick, StickInstance, Surface, Torus, UnitCell,
+};
 
 pub trait Logger: Send + Sync + Copy {
     fn log(&self, message: impl std::fmt::Display);
@@ -54,8 +57,18 @@ pub enum Shape {
     Stick(Stick),
     Molecules(Molecule),
     Protein(Protein),
-    Qudrate, // Custom(CustomShape),
-             // ...
+    Surface(Surface),
+    Isosurface(Isosurface),
+    Mesh(Mesh),
+    Labels(Labels),
+    Arrow(Arrow),
+    Measurement(Measurement),
+    UnitCell(UnitCell),
+    Cuboid(Cuboid),
+    Cone(Cone),
+    Torus(Torus),
+    Plane(Plane),
+    Ellipsoid(Ellipsoid),
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -79,6 +92,27 @@ impl Interpolatable for Shape {
             (Shape::Molecules(a), Shape::Molecules(b)) => {
                 Shape::Molecules(a.interpolate(b, t, logger))
             }
+            (Shape::Protein(a), Shape::Protein(b)) => Shape::Protein(a.interpolate(b, t, logger)),
+            (Shape::Surface(a), Shape::Surface(b)) => Shape::Surface(a.interpolate(b, t, logger)),
+            (Shape::Isosurface(a), Shape::Isosurface(b)) => {
+                Shape::Isosurface(a.interpolate(b, t, logger))
+            }
+            (Shape::Mesh(a), Shape::Mesh(b)) => Shape::Mesh(a.interpolate(b, t, logger)),
+            (Shape::Labels(a), Shape::Labels(b)) => Shape::Labels(a.interpolate(b, t, logger)),
+            (Shape::Arrow(a), Shape::Arrow(b)) => Shape::Arrow(a.interpolate(b, t, logger)),
+            (Shape::Measurement(a), Shape::Measurement(b)) => {
+                Shape::Measurement(a.interpolate(b, t, logger))
+            }
+            (Shape::UnitCell(a), Shape::UnitCell(b)) => {
+                Shape::UnitCell(a.interpolate(b, t, logger))
+            }
+            (Shape::Cuboid(a), Shape::Cuboid(b)) => Shape::Cuboid(a.interpolate(b, t, logger)),
+            (Shape::Cone(a), Shape::Cone(b)) => Shape::Cone(a.interpolate(b, t, logger)),
+            (Shape::Torus(a), Shape::Torus(b)) => Shape::Torus(a.interpolate(b, t, logger)),
+            (Shape::Plane(a), Shape::Plane(b)) => Shape::Plane(a.interpolate(b, t, logger)),
+            (Shape::Ellipsoid(a), Shape::Ellipsoid(b)) => {
+                Shape::Ellipsoid(a.interpolate(b, t, logger))
+            }
             _ => self.clone(), // 如果类型不匹配，可以选择不插值或做默认处理
         }
     }
@@ -88,12 +122,15 @@ impl Interpolatable for Shape {
 pub struct InstanceGroups {
     pub spheres: Vec<SphereInstance>,
     pub sticks: Vec<StickInstance>,
+    /// Cones share the stick layout: base center at `start`, tip at `end`.
+    pub cones: Vec<StickInstance>,
 }
 
 impl InstanceGroups {
     pub fn merge(&mut self, mut other: InstanceGroups) {
         self.spheres.append(&mut other.spheres);
         self.sticks.append(&mut other.sticks);
+        self.cones.append(&mut other.cones);
     }
 }
 
@@ -109,6 +146,21 @@ impl IntoInstanceGroups for Shape {
                 let m_groups = m.to_instance_group(scale);
                 groups.merge(m_groups);
             }
+            Shape::Protein(p) => {
+                groups.merge(p.to_instance_group(scale));
+            }
+            Shape::Arrow(a) => {
+                groups.merge(a.to_instance_group(scale));
+            }
+            Shape::Measurement(m) => {
+                groups.merge(m.to_instance_group(scale));
+            }
+            Shape::UnitCell(c) => {
+                groups.merge(c.to_instance_group(scale));
+            }
+            Shape::Plane(p) => {
+                groups.merge(p.to_instance_group(scale));
+            }
             _ => {}
         }
         groups
@@ -130,7 +182,18 @@ impl ToMesh for Shape {
             Shape::Stick(s) => s.to_mesh(scale),
             Shape::Molecules(s) => s.to_mesh(scale),
             Shape::Protein(s) => s.to_mesh(scale),
-            Shape::Qudrate => todo!(),
+            Shape::Surface(s) => s.to_mesh(scale),
+            Shape::Isosurface(s) => s.to_mesh(scale),
+            Shape::Mesh(s) => s.to_mesh(scale),
+            Shape::Labels(s) => s.to_mesh(scale),
+            Shape::Arrow(s) => s.to_mesh(scale),
+            Shape::Measurement(s) => s.to_mesh(scale),
+            Shape::UnitCell(s) => s.to_mesh(scale),
+            Shape::Cuboid(s) => s.to_mesh(scale),
+            Shape::Cone(s) => s.to_mesh(scale),
+            Shape::Torus(s) => s.to_mesh(scale),
+            Shape::Plane(s) => s.to_mesh(scale),
+            Shape::Ellipsoid(s) => s.to_mesh(scale),
         }
     }
 }
@@ -143,6 +206,10 @@ pub struct MeshData {
     pub colors: Option<Vec<Vec4>>,
     pub transform: Option<Mat4>, // 可选位移旋转缩放
     pub is_wireframe: bool,
+    /// Line segments as pairs of vertex indices, drawn as `GL_LINES` next to the triangles.
+    pub line_indices: Vec<u32>,
+    /// Width in pixels of wireframe edges and `line_indices`, where the driver supports it.
+    pub line_width: Option<f32>,
 }
 
 impl MeshData {
@@ -167,6 +234,51 @@ impl MeshData {
 
         // append indices with offset
         self.indices.extend(other.indices.iter().map(|i| i + base));
+        self.line_indices
+            .extend(other.line_indices.iter().map(|i| i + base));
+        self.line_width = match (self.line_width, other.line_width) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Replaces the normals with area-weighted averages of the adjacent face normals.
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![Vec3::ZERO; self.vertices.len()];
+        for tri in self.indices.chunks_exact(3) {
+            let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| i as usize);
+            let n =
+                (self.vertices[b] - self.vertices[a]).cross(self.vertices[c] - self.vertices[a]);
+            normals[a] += n;
+            normals[b] += n;
+            normals[c] += n;
+        }
+        self.normals = normals.into_iter().map(|n| n.normalize_or_zero()).collect();
+    }
+}
+
+/// Maps `t ∈ [0, 1]` onto a blue → white → red gradient.
+pub fn blue_white_red(t: f32) -> Vec3 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        let s = t * 2.0;
+        Vec3::new(s, s, 1.0)
+    } else {
+        let s = (1.0 - t) * 2.0;
+        Vec3::new(1.0, s, s)
+    }
+}
+
+/// Blue (t = 0) through green and yellow to red (t = 1).
+pub fn rainbow(t: f32) -> Vec3 {
+    // 色相从 240°（蓝）到 0°（红）
+    let h = (1.0 - t.clamp(0.0, 1.0)) * 4.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    match h as u32 {
+        0 => Vec3::new(1.0, x, 0.0),
+        1 => Vec3::new(x, 1.0, 0.0),
+        2 => Vec3::new(0.0, 1.0, x),
+        _ => Vec3::new(0.0, x, 1.0),
     }
 }
 
@@ -198,4 +310,23 @@ pub trait VisualShape {
         self.style_mut().opacity = opacity;
         self
     }
+
+    /// Draw mesh based shapes as triangle edges instead of filled faces.
+    fn wireframe(mut self, wireframe: bool) -> Self
+    where
+        Self: Sized,
+    {
+        self.style_mut().wireframe = wireframe;
+        self
+    }
+
+    /// Width in pixels of lines and wireframe edges. Wide lines are not available
+    /// on every driver; unsupported widths fall back to the nearest supported one.
+    fn line_width(mut self, width: f32) -> Self
+    where
+        Self: Sized,
+    {
+        self.style_mut().line_width = Some(width);
+        self
+    }
 }
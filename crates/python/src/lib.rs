@@ -8,7 +8,10 @@ use std::ffi::CStr;
 
 use pyo3::{ffi::c_str, prelude::*};
 
-use crate::shapes::{PyMolecule, PyProtein, PySphere, PyStick};
+use crate::shapes::{
+    PyArrow, PyBox, PyCone, PyEllipsoid, PyIsosurface, PyLabels, PyMeasurement, PyMesh, PyMolecule,
+    PyPlane, PyProtein, PySphere, PyStick, PySurface, PyTorus, PyUnitCell,
+};
 use cosmol_viewer_core::{NativeGuiViewer, scene::Scene as _Scene};
 use cosmol_viewer_wasm::NotebookViewer;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
@@ -160,7 +163,7 @@ impl Scene {
         Add a shape to the scene without an explicit ID.
 
         # Args
-        - shape: A shape instance (Sphere, Stick, Molecule, or Protein).
+        - shape: A shape instance (Sphere, Stick, Molecule, Protein, Surface, or Isosurface).
 
         # Example
         ```python
@@ -181,6 +184,18 @@ impl Scene {
         try_add!(PyStick);
         try_add!(PyMolecule);
         try_add!(PyProtein);
+        try_add!(PySurface);
+        try_add!(PyIsosurface);
+        try_add!(PyMesh);
+        try_add!(PyLabels);
+        try_add!(PyArrow);
+        try_add!(PyMeasurement);
+        try_add!(PyUnitCell);
+        try_add!(PyBox);
+        try_add!(PyCone);
+        try_add!(PyTorus);
+        try_add!(PyPlane);
+        try_add!(PyEllipsoid);
 
         let type_name = shape
             .get_type()
@@ -190,7 +205,7 @@ impl Scene {
 
         Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
             "add_shape(): unsupported shape type '{type_name}'. \
-             Expected one of: Sphere, Stick, Molecule, Protein"
+             Expected one of: Sphere, Stick, Molecule, Protein, Surface, Isosurface, Mesh, Labels, Arrow, Measurement, UnitCell, Box, Cone, Torus, Plane, Ellipsoid"
         )))
     }
 
@@ -221,6 +236,18 @@ impl Scene {
         try_add!(PyStick);
         try_add!(PyMolecule);
         try_add!(PyProtein);
+        try_add!(PySurface);
+        try_add!(PyIsosurface);
+        try_add!(PyMesh);
+        try_add!(PyLabels);
+        try_add!(PyArrow);
+        try_add!(PyMeasurement);
+        try_add!(PyUnitCell);
+        try_add!(PyBox);
+        try_add!(PyCone);
+        try_add!(PyTorus);
+        try_add!(PyPlane);
+        try_add!(PyEllipsoid);
 
         let type_name = shape
             .get_type()
@@ -230,7 +257,7 @@ impl Scene {
 
         Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
             "add_shape(): unsupported shape type '{type_name}'. \
-             Expected one of: Sphere, Stick, Molecules, Protein"
+             Expected one of: Sphere, Stick, Molecules, Protein, Surface, Isosurface, Mesh, Labels, Arrow, Measurement, UnitCell, Box, Cone, Torus, Plane, Ellipsoid"
         )))
     }
 
@@ -264,6 +291,18 @@ impl Scene {
         update_with!(PyStick);
         update_with!(PyMolecule);
         update_with!(PyProtein);
+        update_with!(PySurface);
+        update_with!(PyIsosurface);
+        update_with!(PyMesh);
+        update_with!(PyLabels);
+        update_with!(PyArrow);
+        update_with!(PyMeasurement);
+        update_with!(PyUnitCell);
+        update_with!(PyBox);
+        update_with!(PyCone);
+        update_with!(PyTorus);
+        update_with!(PyPlane);
+        update_with!(PyEllipsoid);
 
         let type_name = shape
             .get_type()
@@ -351,6 +390,24 @@ impl Scene {
         self.inner.use_black_background();
     }
 
+    #[doc = r#"
+        Show a Ramachandran (phi/psi) plot of the proteins in the scene.
+
+        Hovering a point in the plot highlights the residue in the 3D view, and
+        hovering a CA atom in the 3D view highlights its point in the plot.
+
+        # Args
+        - show: Whether to show the plot panel.
+
+        # Example
+        ```python
+        scene.show_ramachandran(True)
+        ```
+    "#]
+    pub fn show_ramachandran(&mut self, show: bool) {
+        self.inner.show_ramachandran(show);
+    }
+
     #[gen_stub(skip)]
     fn __repr__(&self) -> String {
         format!("RustScene({:?})", self.inner)
@@ -706,6 +763,18 @@ fn cosmol_viewer(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyStick>()?;
     m.add_class::<PyMolecule>()?;
     m.add_class::<PyProtein>()?;
+    m.add_class::<PySurface>()?;
+    m.add_class::<PyIsosurface>()?;
+    m.add_class::<PyMesh>()?;
+    m.add_class::<PyLabels>()?;
+    m.add_class::<PyArrow>()?;
+    m.add_class::<PyMeasurement>()?;
+    m.add_class::<PyUnitCell>()?;
+    m.add_class::<PyBox>()?;
+    m.add_class::<PyCone>()?;
+    m.add_class::<PyTorus>()?;
+    m.add_class::<PyPlane>()?;
+    m.add_class::<PyEllipsoid>()?;
     Ok(())
 }
 
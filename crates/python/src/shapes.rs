@@ -1,12 +1,21 @@
 use crate::PyErr;
 use crate::PyResult;
 use cosmol_viewer_core::{
-    shapes::{Molecule, Protein, Sphere, Stick},
+    shapes::{
+        Arrow, CartoonStyle, Cone, Cuboid, Ellipsoid, HeteroOptions, Isosurface, Labels,
+        Measurement, MeasurementError, Mesh, Molecule, MoleculeStyle, Plane, Protein,
+        ProteinColoring, ProteinStyle, Sphere, Stick, Surface, SurfaceKind, Torus, UnitCell,
+    },
+    utils::MeshData,
     utils::VisualShape,
 };
-use pyo3::{PyRefMut, pyclass, pymethods};
+use pyo3::types::{PyBytes, PyBytesMethods};
+use pyo3::{Bound, PyRef, PyRefMut, pyclass, pymethods};
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 
+/// (chain_id, residue_sn, phi, psi, omega)
+type ResidueDihedrals = (String, usize, Option<f32>, Option<f32>, Option<f32>);
+
 #[gen_stub_pyclass]
 #[pyclass(name = "Sphere")]
 #[derive(Clone)]
@@ -161,6 +170,86 @@ impl PyMolecule {
         })
     }
 
+    #[staticmethod]
+    #[doc = r#"
+        Create a Molecule from the atoms of a Gaussian cube file.
+
+        Bonds are guessed from covalent radii. Use `Isosurface.from_cube` for the volumetric data.
+
+        # Args
+        - cube: The cube file content as a string.
+    "#]
+    pub fn from_cube(cube: &str) -> PyResult<Self> {
+        let (inner, _) = Molecule::from_cube(cube)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a Molecule from the first model of a PDB file, keeping ANISOU tensors.
+
+        # Args
+        - pdb: The file content as a string.
+    "#]
+    pub fn from_pdb(pdb: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: Molecule::from_pdb(pdb)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a Molecule from all atoms of an mmCIF file, keeping `_atom_site_anisotrop` tensors.
+
+        # Args
+        - mmcif: The file content as a string.
+    "#]
+    pub fn from_mmcif(mmcif: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: Molecule::from_mmcif(mmcif)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (mmcif, water = false, ions = true))]
+    #[doc = r#"
+        Extract the hetero groups (ligands, modified residues, ions) of an mmCIF file, one
+        Molecule per residue. Bonds are perceived from distances.
+
+        # Args
+        - mmcif: The file content as a string.
+        - water: Whether to include waters, merged into a single Molecule. Defaults to False.
+        - ions: Whether to include single-atom groups such as metal ions. Defaults to True.
+
+        # Example
+        ```python
+        ligands = Molecule.hetero_groups_from_mmcif(content, water=False, ions=False)
+        ```
+    "#]
+    pub fn hetero_groups_from_mmcif(mmcif: &str, water: bool, ions: bool) -> PyResult<Vec<Self>> {
+        let groups = Molecule::hetero_groups_from_mmcif(mmcif, HeteroOptions { water, ions })
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        Ok(groups.into_iter().map(|inner| Self { inner }).collect())
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a Molecule from the asymmetric unit of a small-molecule CIF file, keeping
+        `_atom_site_aniso` tensors.
+
+        # Args
+        - cif: The file content as a string.
+    "#]
+    pub fn from_cif(cif: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: Molecule::from_cif(cif)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
     pub fn get_center(slf: PyRefMut<'_, Self>) -> [f32; 3] {
         slf.inner.clone().get_center()
     }
@@ -170,6 +259,204 @@ impl PyMolecule {
         slf
     }
 
+    #[doc = r#"
+        Indices of the atoms matching a selection expression.
+
+        Terms: `elem`, `name`, `resn`, `chain` (values separated by spaces or `+`, a
+        trailing `*` matches a prefix), `resi` and `index` (numbers or ranges like
+        `10-20`), and the keywords `all`, `none`, `hetero`, `water`, `hydrogen`,
+        `protein`, `backbone`, `sidechain`. Combine with `and`, `or`, `not`,
+        `byres`, `within <Å> of` and parentheses. Residue terms need a molecule read
+        from a PDB or mmCIF file.
+
+        # Args
+        - expr: The selection expression.
+
+        # Returns
+        - list[int]: Atom indices in ascending order.
+
+        # Example
+        ```python
+        pocket = mol.select("byres (protein and within 5 of resn LIG)")
+        mol = mol.color_atoms(pocket, [1.0, 0.6, 0.0])
+        ```
+    "#]
+    pub fn select(slf: PyRef<'_, Self>, expr: &str) -> PyResult<Vec<usize>> {
+        slf.inner
+            .select(expr)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    #[doc = r#"
+        Return a new Molecule with only the given atoms and the bonds between them.
+
+        Combine with `select` to style or hide part of a structure, e.g.
+        `mol.subset(mol.select("not water"))`.
+
+        # Args
+        - indices: Atom indices to keep.
+    "#]
+    pub fn subset(slf: PyRef<'_, Self>, indices: Vec<usize>) -> Self {
+        Self {
+            inner: slf.inner.subset(&indices),
+        }
+    }
+
+    #[doc = r#"
+        Color the given atoms, leaving the others unchanged.
+
+        # Args
+        - indices: Atom indices, e.g. from `select`.
+        - color: RGB color.
+    "#]
+    pub fn color_atoms(
+        mut slf: PyRefMut<'_, Self>,
+        indices: Vec<usize>,
+        color: [f32; 3],
+    ) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_atoms(&indices, color);
+        slf
+    }
+
+    #[doc = r#"
+        Set the molecule representation.
+
+        # Args
+        - style: "ball_and_stick" (default), "stick" (licorice), "sphere" (CPK spacefill
+          with van der Waals radii), "line" or "ortep".
+
+        # Example
+        ```python
+        mol = Molecule.from_sdf(sdf).style("sphere").radius_scale(0.8)
+        ```
+    "#]
+    pub fn style<'a>(mut slf: PyRefMut<'a, Self>, style: &str) -> PyResult<PyRefMut<'a, Self>> {
+        let style = match style.to_ascii_lowercase().as_str() {
+            "ball_and_stick" | "ballandstick" => MoleculeStyle::BallAndStick,
+            "stick" | "licorice" => MoleculeStyle::Stick,
+            "sphere" | "spacefill" | "cpk" => MoleculeStyle::Sphere,
+            "line" | "lines" => MoleculeStyle::Line,
+            "ortep" => MoleculeStyle::Ortep,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown molecule style '{other}', expected 'ball_and_stick', 'stick', \
+                     'sphere', 'line' or 'ortep'"
+                )));
+            }
+        };
+        slf.inner = slf.inner.clone().style(style);
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Scale atom and bond radii, e.g. 0.8 for a slimmer spacefill.
+    "#]
+    pub fn radius_scale(mut slf: PyRefMut<'_, Self>, scale: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().radius_scale(scale);
+        slf
+    }
+
+    #[doc = r#"
+        Draw bonds as thin lines colored by the element of each half.
+
+        Atoms without bonds are drawn as small crosses. This is the cheapest style and
+        the one to use for very large systems and solvent boxes.
+
+        # Args
+        - width: Line width in pixels (default 1.0). Drivers that only support thin
+          lines, including WebGL, draw 1-pixel lines.
+
+        # Example
+        ```python
+        box = Molecule.from_pdb(open("solvated.pdb").read()).line()
+        ```
+    "#]
+    #[pyo3(signature = (width = 1.0))]
+    pub fn line(mut slf: PyRefMut<'_, Self>, width: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().line(width);
+        slf
+    }
+
+    #[doc = r#"
+        Draw atoms as ORTEP-style thermal ellipsoids with principal-axis rings.
+
+        Atoms without anisotropic displacement parameters are drawn as small spheres.
+
+        # Args
+        - probability: Probability enclosed by each ellipsoid (default 0.5).
+
+        # Example
+        ```python
+        mol = Molecule.from_cif(open("structure.cif").read()).ortep(0.5)
+        ```
+    "#]
+    #[pyo3(signature = (probability = 0.5))]
+    pub fn ortep(mut slf: PyRefMut<'_, Self>, probability: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().ortep(probability);
+        slf
+    }
+
+    #[doc = r#"
+        Replicate the molecule into an NxMxK block of neighboring unit cells.
+
+        # Args
+        - cell: The unit cell or simulation box.
+        - repeat: Number of cells [n, m, k] along a, b and c.
+
+        # Example
+        ```python
+        cell = UnitCell(10.0, 12.0, 8.0, 90.0, 100.0, 90.0).repeat([2, 2, 1])
+        mol = Molecule.from_sdf(sdf).supercell(cell, [2, 2, 1])
+        ```
+    "#]
+    pub fn supercell<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        cell: PyRef<'_, PyUnitCell>,
+        repeat: [usize; 3],
+    ) -> PyRefMut<'py, Self> {
+        slf.inner = slf.inner.supercell(&cell.inner, repeat);
+        slf
+    }
+
+    #[doc = r#"
+        Smallest set of smallest rings (SSSR) of the bond graph.
+
+        # Returns
+        - list[list[int]]: Atom indices of each ring, in ring order.
+    "#]
+    pub fn rings(slf: PyRef<'_, Self>) -> Vec<Vec<usize>> {
+        slf.inner.rings()
+    }
+
+    #[doc = r#"
+        Per-atom solvent accessible surface area (Shrake–Rupley, probe radius 1.4 Å).
+
+        # Returns
+        - list[float]: SASA of each atom in Å².
+    "#]
+    pub fn sasa(slf: PyRef<'_, Self>) -> Vec<f32> {
+        slf.inner.sasa()
+    }
+
+    #[doc = r#"
+        Color atoms by per-atom values on a blue (lowest) to red (highest) gradient.
+
+        # Args
+        - values: One value per atom, e.g. SASA, B-factor or charge.
+    "#]
+    pub fn color_by_values(mut slf: PyRefMut<'_, Self>, values: Vec<f32>) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_by_values(&values);
+        slf
+    }
+
+    #[doc = r#"
+        Color atoms by their solvent accessible surface area.
+    "#]
+    pub fn color_by_sasa(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_by_sasa();
+        slf
+    }
+
     pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
         slf.inner = slf.inner.clone().color(color);
         slf
@@ -233,6 +520,308 @@ impl PyProtein {
         slf
     }
 
+    #[doc = r#"
+        Backbone hydrogen bonds found by DSSP.
+
+        # Returns
+        - list of (chain_id, donor, acceptor, energy): donor (N-H) and acceptor (C=O) are
+          residue indices within the chain, energy is in kcal/mol.
+    "#]
+    pub fn get_hbonds(slf: PyRef<'_, Self>) -> Vec<(String, usize, usize, f32)> {
+        slf.inner
+            .get_hbonds()
+            .into_iter()
+            .map(|(ci, hb)| {
+                (
+                    slf.inner.chains[ci].id.clone(),
+                    hb.donor_idx,
+                    hb.acceptor_idx,
+                    hb.energy,
+                )
+            })
+            .collect()
+    }
+
+    #[doc = r#"
+        Backbone dihedral angles of every residue, in degrees.
+
+        # Returns
+        - list of (chain_id, residue_sn, phi, psi, omega): angles are None where the
+          neighbouring residue is missing or the chain is broken.
+    "#]
+    pub fn get_dihedrals(slf: PyRef<'_, Self>) -> Vec<ResidueDihedrals> {
+        slf.inner
+            .chains
+            .iter()
+            .flat_map(|chain| {
+                chain
+                    .residues
+                    .iter()
+                    .zip(chain.get_dihedrals())
+                    .map(|(res, d)| (chain.id.clone(), res.sns, d.phi, d.psi, d.omega))
+            })
+            .collect()
+    }
+
+    #[doc = r#"
+        Show backbone hydrogen bonds as dashed sticks between N-H and O atoms.
+
+        # Args
+        - show: Whether to draw the hydrogen bonds.
+    "#]
+    pub fn show_hbonds(mut slf: PyRefMut<'_, Self>, show: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().show_hbonds(show);
+        slf
+    }
+
+    #[doc = r#"
+        Unbroken stretches of each chain. Chains break where consecutive CA atoms are more
+        than 4.2 Å apart or residue numbers skip.
+
+        # Returns
+        - list of (chain_id, start, end): residue indices within the chain, end exclusive.
+    "#]
+    pub fn get_segments(slf: PyRef<'_, Self>) -> Vec<(String, usize, usize)> {
+        slf.inner
+            .chains
+            .iter()
+            .flat_map(|chain| {
+                chain
+                    .get_segments()
+                    .into_iter()
+                    .map(|r| (chain.id.clone(), r.start, r.end))
+            })
+            .collect()
+    }
+
+    #[doc = r#"
+        Show chain breaks as dashed lines between the CA atoms around each gap.
+
+        # Args
+        - show: Whether to draw the gaps.
+    "#]
+    pub fn show_gaps(mut slf: PyRefMut<'_, Self>, show: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().show_gaps(show);
+        slf
+    }
+
+    #[doc = r#"
+        Show side chains as element-colored sticks on top of the cartoon.
+
+        # Args
+        - selection: Only residues with an atom matching this selection expression,
+          e.g. "within 5 of resi 45". All residues when omitted.
+
+        # Example
+        ```python
+        prot = Protein.from_mmcif(mmcif).show_sidechains("chain A and resi 40-60")
+        ```
+    "#]
+    #[pyo3(signature = (selection = None))]
+    pub fn show_sidechains<'a>(
+        mut slf: PyRefMut<'a, Self>,
+        selection: Option<&str>,
+    ) -> PyResult<PyRefMut<'a, Self>> {
+        slf.inner = match selection {
+            Some(expr) => slf
+                .inner
+                .clone()
+                .sidechains_for(expr)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?,
+            None => slf.inner.clone().show_sidechains(true),
+        };
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Hide side chains again.
+    "#]
+    pub fn hide_sidechains(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().show_sidechains(false);
+        slf
+    }
+
+    #[doc = r#"
+        Set how the backbone is drawn.
+
+        # Args
+        - style: "cartoon", "tube", "trace" (CA sticks) or "putty" (radius from B-factors).
+
+        # Example
+        ```python
+        prot = Protein.from_mmcif(mmcif).style("tube").color_scheme("chain")
+        ```
+    "#]
+    pub fn style<'a>(mut slf: PyRefMut<'a, Self>, style: &str) -> PyResult<PyRefMut<'a, Self>> {
+        let representation = match style.to_ascii_lowercase().as_str() {
+            "cartoon" => ProteinStyle::Cartoon,
+            "tube" => ProteinStyle::Tube,
+            "trace" => ProteinStyle::Trace,
+            "putty" => ProteinStyle::Putty,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown protein style '{other}', expected 'cartoon', 'tube', 'trace' or 'putty'"
+                )));
+            }
+        };
+        slf.inner = slf.inner.clone().representation(representation);
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Set the tessellation of the backbone mesh.
+
+        # Args
+        - pts_per_res: Spline samples between consecutive residues. Defaults to 5.
+        - ring_segments: Vertices around round cross-sections. Defaults to 32.
+        - auto_lod: Reduce detail automatically for very large assemblies. Defaults to True.
+    "#]
+    #[pyo3(signature = (pts_per_res = 5, ring_segments = 32, auto_lod = true))]
+    pub fn cartoon_quality(
+        mut slf: PyRefMut<'_, Self>,
+        pts_per_res: usize,
+        ring_segments: usize,
+        auto_lod: bool,
+    ) -> PyRefMut<'_, Self> {
+        let mut protein = slf
+            .inner
+            .clone()
+            .cartoon_quality(pts_per_res, ring_segments);
+        protein.cartoon.auto_lod = auto_lod;
+        slf.inner = protein;
+        slf
+    }
+
+    #[doc = r#"
+        Set the cross-section dimensions of the cartoon in Å.
+
+        # Args
+        - helix_width, helix_thickness: Elliptical helix profile. Defaults to 2.0 and 0.5.
+        - sheet_width, sheet_thickness: Flat sheet profile. Defaults to 2.0 and 0.4.
+        - coil_radius: Radius of loops and coils. Defaults to 0.2.
+    "#]
+    #[pyo3(signature = (
+        helix_width = 2.0,
+        helix_thickness = 0.5,
+        sheet_width = 2.0,
+        sheet_thickness = 0.4,
+        coil_radius = 0.2
+    ))]
+    pub fn cartoon_dimensions(
+        mut slf: PyRefMut<'_, Self>,
+        helix_width: f32,
+        helix_thickness: f32,
+        sheet_width: f32,
+        sheet_thickness: f32,
+        coil_radius: f32,
+    ) -> PyRefMut<'_, Self> {
+        let cartoon = CartoonStyle {
+            helix_width,
+            helix_thickness,
+            sheet_width,
+            sheet_thickness,
+            coil_radius,
+            ..slf.inner.cartoon
+        };
+        slf.inner = slf.inner.clone().cartoon(cartoon);
+        slf
+    }
+
+    #[doc = r#"
+        Draw the backbone as a putty tube whose radius follows a per-residue value.
+
+        # Args
+        - values: One value per residue, chain after chain, e.g. RMSF. CA B-factors when omitted.
+          A ValueError is raised when the count differs from the number of residues.
+    "#]
+    #[pyo3(signature = (values = None))]
+    pub fn putty<'a>(
+        mut slf: PyRefMut<'a, Self>,
+        values: Option<Vec<f32>>,
+    ) -> PyResult<PyRefMut<'a, Self>> {
+        let protein = slf.inner.clone().representation(ProteinStyle::Putty);
+        slf.inner = match values {
+            Some(values) => protein
+                .putty_values(values)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?,
+            None => protein,
+        };
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Color the cartoon by a scheme. Colors are blended along the backbone between residues.
+
+        # Args
+        - scheme: "uniform" (the color set with `color`), "chain", "ss" (secondary structure),
+          "rainbow" (N to C terminus), "residue" (basic, acidic, polar, hydrophobic) or
+          "hydrophobicity" (Kyte-Doolittle).
+
+        # Example
+        ```python
+        prot = Protein.from_mmcif(mmcif).color_scheme("rainbow")
+        ```
+    "#]
+    pub fn color_scheme<'a>(
+        mut slf: PyRefMut<'a, Self>,
+        scheme: &str,
+    ) -> PyResult<PyRefMut<'a, Self>> {
+        let coloring = match scheme.to_ascii_lowercase().as_str() {
+            "uniform" => ProteinColoring::Uniform,
+            "chain" => ProteinColoring::Chain,
+            "ss" | "secondary_structure" => ProteinColoring::SecondaryStructure,
+            "rainbow" => ProteinColoring::Rainbow,
+            "residue" | "residue_type" => ProteinColoring::ResidueType,
+            "hydrophobicity" => ProteinColoring::Hydrophobicity,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown color scheme '{other}', expected 'uniform', 'chain', 'ss', \
+                     'rainbow', 'residue' or 'hydrophobicity'"
+                )));
+            }
+        };
+        slf.inner = slf.inner.clone().color_by(coloring);
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Color residues by arbitrary values (blue = lowest, red = highest).
+
+        # Args
+        - values: One value per residue, chain after chain, e.g. B-factors. A ValueError
+          is raised when the count differs from the number of residues.
+    "#]
+    pub fn color_by_values<'a>(
+        mut slf: PyRefMut<'a, Self>,
+        values: Vec<f32>,
+    ) -> PyResult<PyRefMut<'a, Self>> {
+        slf.inner = slf
+            .inner
+            .clone()
+            .color_by_values(values)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Per-residue solvent accessible surface area (Shrake–Rupley, probe radius 1.4 Å).
+
+        # Returns
+        - list[float]: SASA of each residue in Å², chain after chain, as expected by
+          `color_by_values` and `putty`.
+    "#]
+    pub fn residue_sasa(slf: PyRef<'_, Self>) -> Vec<f32> {
+        slf.inner.residue_sasa()
+    }
+
+    #[doc = r#"
+        Color residues by their solvent accessible surface area.
+    "#]
+    pub fn color_by_sasa(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_by_sasa();
+        slf
+    }
+
     pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
         slf.inner = slf.inner.clone().color(color);
         slf
@@ -248,3 +837,1288 @@ impl PyProtein {
         slf
     }
 }
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Surface")]
+#[derive(Clone)]
+#[doc = r#"
+    A molecular surface built from a Molecule or Protein.
+
+    The surface is either solvent-excluded (default) or Gaussian, extracted
+    with marching cubes and colored by the nearest atom.
+
+    # Example
+    ```python
+    surf = Surface.from_molecule(mol).kind("gaussian").opacity(0.6)
+    ```
+"#]
+pub struct PySurface {
+    pub inner: Surface,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PySurface {
+    #[staticmethod]
+    pub fn from_molecule(molecule: PyRef<'_, PyMolecule>) -> Self {
+        Self {
+            inner: Surface::from_molecule(&molecule.inner),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Surface of all protein atoms, side chains included.
+    "#]
+    pub fn from_protein(protein: PyRef<'_, PyProtein>) -> Self {
+        Self {
+            inner: Surface::from_protein(&protein.inner),
+        }
+    }
+
+    #[doc = r#"
+        Set the surface type.
+
+        # Args
+        - kind: "ses" (solvent-excluded) or "gaussian".
+    "#]
+    pub fn kind<'a>(mut slf: PyRefMut<'a, Self>, kind: &str) -> PyResult<PyRefMut<'a, Self>> {
+        let kind = match kind.to_ascii_lowercase().as_str() {
+            "ses" | "solvent_excluded" => SurfaceKind::SolventExcluded,
+            "gaussian" => SurfaceKind::Gaussian,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown surface kind '{other}', expected 'ses' or 'gaussian'"
+                )));
+            }
+        };
+        slf.inner = slf.inner.clone().kind(kind);
+        Ok(slf)
+    }
+
+    #[doc = r#"
+        Solvent probe radius in Å, 1.4 by default. 0 gives the van der Waals surface.
+    "#]
+    pub fn probe_radius(mut slf: PyRefMut<'_, Self>, probe_radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().probe_radius(probe_radius);
+        slf
+    }
+
+    #[doc = r#"
+        Grid spacing in Å, 0.5 by default. Smaller values give finer surfaces.
+    "#]
+    pub fn resolution(mut slf: PyRefMut<'_, Self>, resolution: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().resolution(resolution);
+        slf
+    }
+
+    #[doc = r#"
+        Gaussian falloff, 2.0 by default. Larger values follow the atoms more tightly.
+    "#]
+    pub fn smoothness(mut slf: PyRefMut<'_, Self>, smoothness: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().smoothness(smoothness);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Isosurface")]
+#[derive(Clone)]
+#[doc = r#"
+    Contour surface of a volumetric density map, e.g. from cryo-EM or crystallography.
+
+    # Example
+    ```python
+    data = open("emd_1234.map", "rb").read()
+    density = Isosurface.from_ccp4(data).sigma(1.5).crop_around_protein(prot, 5.0).wireframe(True)
+    ```
+"#]
+pub struct PyIsosurface {
+    pub inner: Isosurface,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyIsosurface {
+    #[staticmethod]
+    #[doc = r#"
+        Create an isosurface from a CCP4 / MRC map file, contoured at 1σ.
+
+        # Args
+        - data: The raw bytes of the map file.
+    "#]
+    pub fn from_ccp4(data: &Bound<'_, PyBytes>) -> PyResult<Self> {
+        Ok(Self {
+            inner: Isosurface::from_ccp4(data.as_bytes())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a dual-lobe isosurface from a Gaussian cube file.
+
+        The positive lobe (blue) is contoured at `+iso`, the negative one (red) at `-iso`.
+
+        # Args
+        - cube: The cube file content as a string.
+        - iso: Contour value, e.g. 0.02 for orbitals.
+    "#]
+    pub fn from_cube(cube: &str, iso: f32) -> PyResult<Self> {
+        let (_, grid) = Molecule::from_cube(cube)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        Ok(Self {
+            inner: Isosurface::orbital(grid, iso),
+        })
+    }
+
+    #[doc = r#"
+        Also draw the `-level` contour in the given color, e.g. the negative phase of an orbital.
+    "#]
+    pub fn dual_lobe(mut slf: PyRefMut<'_, Self>, negative_color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().dual_lobe(negative_color);
+        slf
+    }
+
+    #[doc = r#"
+        Set the contour level in units of the map RMS above the mean.
+    "#]
+    pub fn sigma(mut slf: PyRefMut<'_, Self>, sigma: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().sigma(sigma);
+        slf
+    }
+
+    #[doc = r#"
+        Set the contour level as an absolute map value.
+    "#]
+    pub fn level(mut slf: PyRefMut<'_, Self>, level: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().level(level);
+        slf
+    }
+
+    pub fn get_sigma(slf: PyRef<'_, Self>) -> f32 {
+        slf.inner.get_sigma()
+    }
+
+    #[doc = r#"
+        Keep only the part of the map within `padding` Å of the given points.
+
+        # Args
+        - points: List of [x, y, z] coordinates, e.g. atoms of a selection.
+        - padding: Margin around the points in Å.
+    "#]
+    pub fn crop_to_points(
+        mut slf: PyRefMut<'_, Self>,
+        points: Vec<[f32; 3]>,
+        padding: f32,
+    ) -> PyRefMut<'_, Self> {
+        let points: Vec<_> = points.into_iter().map(Into::into).collect();
+        slf.inner = slf.inner.clone().crop_to_points(&points, padding);
+        slf
+    }
+
+    pub fn crop_around_molecule<'a>(
+        mut slf: PyRefMut<'a, Self>,
+        molecule: PyRef<'_, PyMolecule>,
+        padding: f32,
+    ) -> PyRefMut<'a, Self> {
+        slf.inner = slf
+            .inner
+            .clone()
+            .crop_around_molecule(&molecule.inner, padding);
+        slf
+    }
+
+    pub fn crop_around_protein<'a>(
+        mut slf: PyRefMut<'a, Self>,
+        protein: PyRef<'_, PyProtein>,
+        padding: f32,
+    ) -> PyRefMut<'a, Self> {
+        slf.inner = slf
+            .inner
+            .clone()
+            .crop_around_protein(&protein.inner, padding);
+        slf
+    }
+
+    #[doc = r#"
+        Draw the contour as a wireframe mesh instead of a solid surface.
+    "#]
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().wireframe(wireframe);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Mesh")]
+#[derive(Clone)]
+#[doc = r#"
+    A triangle mesh built from your own geometry or loaded from an OBJ, PLY or STL file.
+
+    # Args
+    - vertices: (N, 3) array of vertex positions; a numpy array or a list of [x, y, z].
+    - indices: (M, 3) array of vertex indices, one row per triangle.
+    - normals: Optional (N, 3) array of vertex normals; computed from the triangles if omitted.
+    - colors: Optional (N, 3) or (N, 4) array of per-vertex RGB(A) colors in 0..1.
+
+    # Example
+    ```python
+    pocket = Mesh(verts, faces, colors=rgb).opacity(0.6)
+    part = Mesh.from_stl(open("part.stl", "rb").read()).color([0.8, 0.8, 0.8])
+    ```
+"#]
+pub struct PyMesh {
+    pub inner: Mesh,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyMesh {
+    #[new]
+    #[pyo3(signature = (vertices, indices, normals=None, colors=None))]
+    pub fn new(
+        vertices: Vec<[f32; 3]>,
+        indices: Vec<[u32; 3]>,
+        normals: Option<Vec<[f32; 3]>>,
+        colors: Option<Vec<Vec<f32>>>,
+    ) -> PyResult<Self> {
+        let colors = colors
+            .map(|colors| {
+                colors
+                    .into_iter()
+                    .map(|c| match *c.as_slice() {
+                        [r, g, b] => Ok([r, g, b, 1.0].into()),
+                        [r, g, b, a] => Ok([r, g, b, a].into()),
+                        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                            "Colors must have 3 (RGB) or 4 (RGBA) components",
+                        )),
+                    })
+                    .collect::<PyResult<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let inner = Mesh::new(MeshData {
+            vertices: vertices.into_iter().map(Into::into).collect(),
+            normals: normals
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            indices: indices.into_iter().flatten().collect(),
+            colors,
+            ..Default::default()
+        })
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a Mesh from a Wavefront OBJ string.
+
+        # Args
+        - obj: The OBJ file content as a string.
+    "#]
+    pub fn from_obj(obj: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: Mesh::from_obj(obj)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a Mesh from an ASCII or binary PLY file.
+
+        # Args
+        - data: The raw bytes of the PLY file.
+    "#]
+    pub fn from_ply(data: &Bound<'_, PyBytes>) -> PyResult<Self> {
+        Ok(Self {
+            inner: Mesh::from_ply(data.as_bytes())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a Mesh from an ASCII or binary STL file.
+
+        # Args
+        - data: The raw bytes of the STL file.
+    "#]
+    pub fn from_stl(data: &Bound<'_, PyBytes>) -> PyResult<Self> {
+        Ok(Self {
+            inner: Mesh::from_stl(data.as_bytes())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    pub fn get_center(slf: PyRef<'_, Self>) -> [f32; 3] {
+        slf.inner.get_center()
+    }
+
+    pub fn centered(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().centered();
+        slf
+    }
+
+    #[doc = r#"
+        Draw the mesh as a wireframe instead of a solid surface.
+    "#]
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().wireframe(wireframe);
+        slf
+    }
+
+    #[doc = r#"
+        Set a uniform color, overriding any per-vertex colors.
+    "#]
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Labels")]
+#[derive(Clone)]
+#[doc = r#"
+    Text labels anchored to 3D positions, always facing the camera.
+
+    # Args
+    - texts: One string per label.
+    - positions: List of [x, y, z] anchors, one per label.
+
+    # Example
+    ```python
+    scene.add_shape(Labels.residues(prot).screen_size(12))
+    scene.add_shape(Labels(["active site"], [[1.0, 2.0, 3.0]]).color([1, 0, 0]))
+    ```
+"#]
+pub struct PyLabels {
+    pub inner: Labels,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyLabels {
+    #[new]
+    pub fn new(texts: Vec<String>, positions: Vec<[f32; 3]>) -> PyResult<Self> {
+        if texts.len() != positions.len() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Got {} texts but {} positions",
+                texts.len(),
+                positions.len()
+            )));
+        }
+        let inner = texts
+            .into_iter()
+            .zip(positions)
+            .fold(Labels::new(), |labels, (text, position)| {
+                labels.add(text, position)
+            });
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Label every atom of a molecule with its element symbol.
+    "#]
+    pub fn atom_elements(molecule: PyRef<'_, PyMolecule>) -> Self {
+        Self {
+            inner: Labels::atom_elements(&molecule.inner),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (molecule, with_element=true))]
+    #[doc = r#"
+        Label every atom of a molecule with its index.
+
+        # Args
+        - molecule: The molecule to label.
+        - with_element: Prefix the index with the element symbol, e.g. `C12`.
+    "#]
+    pub fn atom_indices(molecule: PyRef<'_, PyMolecule>, with_element: bool) -> Self {
+        Self {
+            inner: Labels::atom_indices(&molecule.inner, with_element),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Label every residue of a protein with its name and number at the CA atom.
+    "#]
+    pub fn residues(protein: PyRef<'_, PyProtein>) -> Self {
+        Self {
+            inner: Labels::residues(&protein.inner),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Label every chain of a protein with its ID at the chain centroid.
+    "#]
+    pub fn chains(protein: PyRef<'_, PyProtein>) -> Self {
+        Self {
+            inner: Labels::chains(&protein.inner),
+        }
+    }
+
+    #[doc = r#"
+        Set the text height in screen points, independent of zoom.
+    "#]
+    pub fn screen_size(mut slf: PyRefMut<'_, Self>, size: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().screen_size(size);
+        slf
+    }
+
+    #[doc = r#"
+        Set the text height in Å, so labels scale with zoom like the geometry.
+    "#]
+    pub fn world_size(mut slf: PyRefMut<'_, Self>, size: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().world_size(size);
+        slf
+    }
+
+    #[doc = r#"
+        Shift the labels on screen by [dx, dy] points (+y is down).
+    "#]
+    pub fn offset(mut slf: PyRefMut<'_, Self>, offset: [f32; 2]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().offset(offset);
+        slf
+    }
+
+    #[doc = r#"
+        Hide labels behind atoms (default) or always draw them on top.
+
+        Only atoms drawn as spheres occlude; sticks and surfaces do not.
+    "#]
+    pub fn occlusion(mut slf: PyRefMut<'_, Self>, occlusion: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().occlusion(occlusion);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Arrow")]
+#[derive(Clone)]
+#[doc = r#"
+    An arrow (cylinder shaft with a cone head), e.g. for dipoles, forces or displacements.
+
+    # Args
+    - start: [x, y, z] coordinates of the tail.
+    - end: [x, y, z] coordinates of the tip.
+    - radius: Radius of the shaft.
+
+    # Example
+    ```python
+    arrow = Arrow([0, 0, 0], [0, 0, 3], 0.1).head_ratio(0.3).color([1, 0, 0])
+    ```
+"#]
+pub struct PyArrow {
+    pub inner: Arrow,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyArrow {
+    #[new]
+    pub fn new(start: [f32; 3], end: [f32; 3], radius: f32) -> Self {
+        Self {
+            inner: Arrow::new(start, end, radius),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create an arrow from `origin` along `vector`.
+    "#]
+    pub fn from_vector(origin: [f32; 3], vector: [f32; 3], radius: f32) -> Self {
+        Self {
+            inner: Arrow::from_vector(origin, vector, radius),
+        }
+    }
+
+    pub fn set_start(mut slf: PyRefMut<'_, Self>, start: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_start(start);
+        slf
+    }
+
+    pub fn set_end(mut slf: PyRefMut<'_, Self>, end: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_end(end);
+        slf
+    }
+
+    pub fn set_radius(mut slf: PyRefMut<'_, Self>, radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_radius(radius);
+        slf
+    }
+
+    #[doc = r#"
+        Set the head length as a fraction (0..1) of the arrow length. Default 0.25.
+    "#]
+    pub fn head_ratio(mut slf: PyRefMut<'_, Self>, ratio: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.head_ratio(ratio);
+        slf
+    }
+
+    #[doc = r#"
+        Set the head radius as a multiple of the shaft radius. Default 2.0.
+    "#]
+    pub fn head_width(mut slf: PyRefMut<'_, Self>, width: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.head_width(width);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Measurement")]
+#[derive(Clone)]
+#[doc = r#"
+    A distance, angle or dihedral annotation: dashed lines, an arc and a value label.
+
+    Measurements created from atom indices can follow a molecule added with an ID,
+    so they move with the atoms during animation playback.
+
+    # Example
+    ```python
+    scene.add_shape_with_id("mol", mol)
+    scene.add_shape(Measurement.distance(mol, 0, 5).follow("mol"))
+    scene.add_shape(Measurement.dihedral(mol, 0, 1, 2, 3).follow("mol"))
+    ```
+"#]
+pub struct PyMeasurement {
+    pub inner: Measurement,
+}
+
+fn measurement_error(e: MeasurementError) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyIndexError, _>(e.to_string())
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyMeasurement {
+    #[staticmethod]
+    #[doc = r#"
+        Distance between atoms `i` and `j` of a molecule, in Å.
+    "#]
+    pub fn distance(molecule: PyRef<'_, PyMolecule>, i: usize, j: usize) -> PyResult<Self> {
+        let inner = Measurement::distance(&molecule.inner, [i, j]).map_err(measurement_error)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Angle i-j-k of a molecule, measured at atom `j`, in degrees.
+    "#]
+    pub fn angle(molecule: PyRef<'_, PyMolecule>, i: usize, j: usize, k: usize) -> PyResult<Self> {
+        let inner = Measurement::angle(&molecule.inner, [i, j, k]).map_err(measurement_error)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Dihedral i-j-k-l of a molecule around the j-k bond, in degrees.
+    "#]
+    pub fn dihedral(
+        molecule: PyRef<'_, PyMolecule>,
+        i: usize,
+        j: usize,
+        k: usize,
+        l: usize,
+    ) -> PyResult<Self> {
+        let inner =
+            Measurement::dihedral(&molecule.inner, [i, j, k, l]).map_err(measurement_error)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Distance between two points.
+    "#]
+    pub fn distance_points(a: [f32; 3], b: [f32; 3]) -> Self {
+        Self {
+            inner: Measurement::distance_points(a, b),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Angle a-b-c between three points, measured at `b`.
+    "#]
+    pub fn angle_points(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> Self {
+        Self {
+            inner: Measurement::angle_points(a, b, c),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Dihedral a-b-c-d between four points, around the b-c axis.
+    "#]
+    pub fn dihedral_points(a: [f32; 3], b: [f32; 3], c: [f32; 3], d: [f32; 3]) -> Self {
+        Self {
+            inner: Measurement::dihedral_points(a, b, c, d),
+        }
+    }
+
+    #[doc = r#"
+        Read the atom positions from the scene molecule with this ID on every frame.
+
+        # Args
+        - molecule_id: ID used with `add_shape_with_id` for the molecule.
+    "#]
+    pub fn follow(mut slf: PyRefMut<'_, Self>, molecule_id: String) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().follow(molecule_id);
+        slf
+    }
+
+    #[doc = r#"
+        The measured value: distance in Å or angle in degrees.
+    "#]
+    pub fn value(&self) -> f32 {
+        self.inner.value()
+    }
+
+    pub fn line_radius(mut slf: PyRefMut<'_, Self>, radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().line_radius(radius);
+        slf
+    }
+
+    #[doc = r#"
+        Set the dash and gap lengths of the lines in Å. Default 0.25 and 0.2.
+    "#]
+    pub fn dash(mut slf: PyRefMut<'_, Self>, dash: f32, gap: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().dash(dash, gap);
+        slf
+    }
+
+    #[doc = r#"
+        Set the label text height in screen points. Default 14.
+    "#]
+    pub fn label_size(mut slf: PyRefMut<'_, Self>, size: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().label_size(size);
+        slf
+    }
+
+    pub fn show_label(mut slf: PyRefMut<'_, Self>, show: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().show_label(show);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "UnitCell")]
+#[derive(Clone)]
+#[doc = r#"
+    A crystal unit cell or simulation box, drawn as a wireframe.
+
+    # Args
+    - a, b, c: Cell lengths in Å.
+    - alpha, beta, gamma: Cell angles in degrees.
+
+    # Example
+    ```python
+    cell = UnitCell.from_mmcif(mmcif).repeat([2, 2, 2]).color_axes(True)
+    scene.add_shape(cell)
+    ```
+"#]
+pub struct PyUnitCell {
+    pub inner: UnitCell,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyUnitCell {
+    #[new]
+    pub fn new(a: f32, b: f32, c: f32, alpha: f32, beta: f32, gamma: f32) -> Self {
+        Self {
+            inner: UnitCell::new([a, b, c, alpha, beta, gamma]),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create a box from its three edge vectors, e.g. the box vectors of an MD frame.
+    "#]
+    pub fn from_vectors(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> Self {
+        Self {
+            inner: UnitCell::from_vectors(a, b, c),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Read the unit cell (`_cell`) of an mmCIF file.
+    "#]
+    pub fn from_mmcif(mmcif: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: UnitCell::from_mmcif(mmcif)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Read the unit cell from the CRYST1 record of a PDB file.
+
+        # Args
+        - pdb: The file content as a string.
+    "#]
+    pub fn from_pdb(pdb: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: UnitCell::from_pdb(pdb)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Read the unit cell of a small-molecule CIF file.
+
+        # Args
+        - cif: The file content as a string.
+    "#]
+    pub fn from_cif(cif: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: UnitCell::from_cif(cif)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Read the unit cell of a CCP4/MRC map.
+
+        # Args
+        - data: The raw bytes of the map file.
+    "#]
+    pub fn from_ccp4(data: &Bound<'_, PyBytes>) -> PyResult<Self> {
+        Ok(Self {
+            inner: UnitCell::from_ccp4(data.as_bytes())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create the box spanned by the volumetric grid of a Gaussian cube file.
+    "#]
+    pub fn from_cube(cube: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: UnitCell::from_cube(cube)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        })
+    }
+
+    pub fn set_origin(mut slf: PyRefMut<'_, Self>, origin: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().set_origin(origin);
+        slf
+    }
+
+    #[doc = r#"
+        Draw an NxMxK block of cells.
+
+        # Args
+        - repeat: Number of cells [n, m, k] along a, b and c.
+    "#]
+    pub fn repeat(mut slf: PyRefMut<'_, Self>, repeat: [usize; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().repeat(repeat);
+        slf
+    }
+
+    pub fn set_radius(mut slf: PyRefMut<'_, Self>, radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().set_radius(radius);
+        slf
+    }
+
+    #[doc = r#"
+        Draw the a, b and c edges at the origin in red, green and blue.
+    "#]
+    pub fn color_axes(mut slf: PyRefMut<'_, Self>, color_axes: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_axes(color_axes);
+        slf
+    }
+
+    pub fn get_center(&self) -> [f32; 3] {
+        self.inner.get_center()
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.clone().opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Box")]
+#[derive(Clone)]
+#[doc = r#"
+    A rectangular box, e.g. a bounding box or a docking search space.
+
+    # Args
+    - center: [x, y, z] coordinates of the box center.
+    - size: Edge lengths [x, y, z].
+
+    # Example
+    ```python
+    box = Box([0, 0, 0], [20, 20, 20]).opacity(0.3)
+    box = Box.from_corners([-5, -5, -5], [5, 5, 5]).wireframe(True)
+    ```
+"#]
+pub struct PyBox {
+    pub inner: Cuboid,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyBox {
+    #[new]
+    pub fn new(center: [f32; 3], size: [f32; 3]) -> Self {
+        Self {
+            inner: Cuboid::new(center, size),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create an axis-aligned box between two opposite corners.
+    "#]
+    pub fn from_corners(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self {
+            inner: Cuboid::from_corners(min, max),
+        }
+    }
+
+    pub fn set_center(mut slf: PyRefMut<'_, Self>, center: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_center(center);
+        slf
+    }
+
+    pub fn set_size(mut slf: PyRefMut<'_, Self>, size: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_size(size);
+        slf
+    }
+
+    #[doc = r#"
+        Rotate about the center by `degrees` around `axis`.
+    "#]
+    pub fn rotate(mut slf: PyRefMut<'_, Self>, axis: [f32; 3], degrees: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.rotate(axis, degrees);
+        slf
+    }
+
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.wireframe(wireframe);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Cone")]
+#[derive(Clone)]
+#[doc = r#"
+    A solid cone.
+
+    # Args
+    - base: [x, y, z] center of the base disk.
+    - tip: [x, y, z] coordinates of the tip.
+    - radius: Radius of the base disk.
+
+    # Example
+    ```python
+    cone = Cone([0, 0, 0], [0, 0, 2], 0.5).color([1, 0.5, 0])
+    ```
+"#]
+pub struct PyCone {
+    pub inner: Cone,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyCone {
+    #[new]
+    pub fn new(base: [f32; 3], tip: [f32; 3], radius: f32) -> Self {
+        Self {
+            inner: Cone::new(base, tip, radius),
+        }
+    }
+
+    pub fn set_base(mut slf: PyRefMut<'_, Self>, base: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_base(base);
+        slf
+    }
+
+    pub fn set_tip(mut slf: PyRefMut<'_, Self>, tip: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_tip(tip);
+        slf
+    }
+
+    pub fn set_radius(mut slf: PyRefMut<'_, Self>, radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_radius(radius);
+        slf
+    }
+
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.wireframe(wireframe);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Torus")]
+#[derive(Clone)]
+#[doc = r#"
+    A ring (torus) around an axis.
+
+    # Args
+    - center: [x, y, z] coordinates of the ring center.
+    - normal: Axis the ring goes around.
+    - radius: Distance from the center to the middle of the tube.
+    - tube_radius: Radius of the tube.
+
+    # Example
+    ```python
+    ring = Torus([0, 0, 0], [0, 0, 1], 1.4, 0.1).color([0.2, 0.6, 1.0])
+    ```
+"#]
+pub struct PyTorus {
+    pub inner: Torus,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyTorus {
+    #[new]
+    pub fn new(center: [f32; 3], normal: [f32; 3], radius: f32, tube_radius: f32) -> Self {
+        Self {
+            inner: Torus::new(center, normal, radius, tube_radius),
+        }
+    }
+
+    pub fn set_center(mut slf: PyRefMut<'_, Self>, center: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_center(center);
+        slf
+    }
+
+    pub fn set_normal(mut slf: PyRefMut<'_, Self>, normal: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_normal(normal);
+        slf
+    }
+
+    pub fn set_radius(mut slf: PyRefMut<'_, Self>, radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_radius(radius);
+        slf
+    }
+
+    pub fn set_tube_radius(mut slf: PyRefMut<'_, Self>, tube_radius: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_tube_radius(tube_radius);
+        slf
+    }
+
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.wireframe(wireframe);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Plane")]
+#[derive(Clone)]
+#[doc = r#"
+    A flat rectangle, e.g. a membrane or mirror plane, with an optional grid.
+
+    # Args
+    - center: [x, y, z] coordinates of the rectangle center.
+    - normal: Normal vector of the plane.
+    - size: [width, height] of the rectangle.
+
+    # Example
+    ```python
+    membrane = Plane([0, 0, 0], [0, 0, 1], [60, 60]).grid(12).opacity(0.4)
+    ```
+"#]
+pub struct PyPlane {
+    pub inner: Plane,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyPlane {
+    #[new]
+    pub fn new(center: [f32; 3], normal: [f32; 3], size: [f32; 2]) -> Self {
+        Self {
+            inner: Plane::new(center, normal, size),
+        }
+    }
+
+    pub fn set_center(mut slf: PyRefMut<'_, Self>, center: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_center(center);
+        slf
+    }
+
+    pub fn set_normal(mut slf: PyRefMut<'_, Self>, normal: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_normal(normal);
+        slf
+    }
+
+    pub fn set_size(mut slf: PyRefMut<'_, Self>, size: [f32; 2]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_size(size);
+        slf
+    }
+
+    #[doc = r#"
+        Draw grid lines splitting each side into `cells` cells (0 for no grid).
+    "#]
+    pub fn grid(mut slf: PyRefMut<'_, Self>, cells: u32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.grid(cells);
+        slf
+    }
+
+    pub fn grid_color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.grid_color(color);
+        slf
+    }
+
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.wireframe(wireframe);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.opacity(opacity);
+        slf
+    }
+}
+
+#[gen_stub_pyclass]
+#[pyclass(name = "Ellipsoid")]
+#[derive(Clone)]
+#[doc = r#"
+    A sphere stretched along three perpendicular axes.
+
+    # Args
+    - center: [x, y, z] coordinates of the center.
+    - radii: Semi-axis lengths [rx, ry, rz].
+
+    # Example
+    ```python
+    e = Ellipsoid([0, 0, 0], [2.0, 1.0, 0.5]).rotate([0, 0, 1], 45)
+    e = Ellipsoid.from_axes([0, 0, 0], [[2, 0, 0], [0, 1, 0], [0, 0, 0.5]])
+    ```
+"#]
+pub struct PyEllipsoid {
+    pub inner: Ellipsoid,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyEllipsoid {
+    #[new]
+    pub fn new(center: [f32; 3], radii: [f32; 3]) -> Self {
+        Self {
+            inner: Ellipsoid::new(center, radii),
+        }
+    }
+
+    #[staticmethod]
+    #[doc = r#"
+        Create an ellipsoid from its three perpendicular semi-axis vectors.
+    "#]
+    pub fn from_axes(center: [f32; 3], axes: [[f32; 3]; 3]) -> Self {
+        Self {
+            inner: Ellipsoid::from_axes(center, axes),
+        }
+    }
+
+    pub fn set_center(mut slf: PyRefMut<'_, Self>, center: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_center(center);
+        slf
+    }
+
+    pub fn set_radii(mut slf: PyRefMut<'_, Self>, radii: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.set_radii(radii);
+        slf
+    }
+
+    #[doc = r#"
+        Rotate about the center by `degrees` around `axis`.
+    "#]
+    pub fn rotate(mut slf: PyRefMut<'_, Self>, axis: [f32; 3], degrees: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.rotate(axis, degrees);
+        slf
+    }
+
+    pub fn wireframe(mut slf: PyRefMut<'_, Self>, wireframe: bool) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.wireframe(wireframe);
+        slf
+    }
+
+    pub fn color(mut slf: PyRefMut<'_, Self>, color: [f32; 3]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color(color);
+        slf
+    }
+
+    pub fn color_rgba(mut slf: PyRefMut<'_, Self>, color: [f32; 4]) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.color_rgba(color);
+        slf
+    }
+
+    pub fn opacity(mut slf: PyRefMut<'_, Self>, opacity: f32) -> PyRefMut<'_, Self> {
+        slf.inner = slf.inner.opacity(opacity);
+        slf
+    }
+}